@@ -44,55 +44,2024 @@
 //!     assert_eq!(packet0, packet1);
 //! }
 //! ```
+//!
+//! # Default byte order
+//!
+//! Instead of marking every field, the byte order can be specified for the whole struct using
+//! `#[struct_deser(byte_order = "be")]`. Fields marked with `#[le]` or `#[be]` override it. Bytes,
+//! endian wrappers and nested derived types (and arrays of them) ignore it. Fields of types
+//! implementing the traits manually can opt out using `#[struct_deser(byte_order = "none")]`.
+//!
+//! Fields marked with `#[ne]` (or `byte_order = "ne"`) use the byte order of the target platform.
+//!
+//! Alternatively, fields can use the types from `struct_deser::byteorder` module, such as `U16Be`,
+//! which have fixed byte order and don't need any attributes. The default byte order of the struct
+//! doesn't affect them, nor arrays of them, such as `[U16Be; 4]`.
+//!
+//! # Byte order chosen by the caller
+//!
+//! Structs marked with `#[struct_deser(ordered)]` implement `FromBytesOrdered` and
+//! `IntoBytesOrdered` instead of `FromBytes` and `IntoBytes`. Their unannotated fields use the
+//! byte order requested by the caller, so the same struct can be nested as `#[be] inner: Header`
+//! in one container and as `#[le] inner: Header` in another. If default byte order is specified
+//! too, both families of traits are implemented.
+//!
+//! The byte order can be chosen at runtime too, using `Endian` and the `*_with` methods of the
+//! ordered traits, e.g. `Header::from_bytes_with(bytes, Endian::Big)`. This is useful for formats
+//! declaring their byte order in a header.
+//!
+//! # Constants
+//!
+//! Fields marked with `#[struct_deser(const = "expr")]` (or `constant = "expr"`) always serialize
+//! the value of `expr`, regardless of the value of the field, and fallible deserialization fails
+//! with `Error::InvalidConstant` if the bytes hold a different value. Similarly, fields marked with
+//! `#[struct_deser(magic = b"...")]` are serialized as the given bytes. Magic bytes which shouldn't
+//! appear in the struct at all are specified on the struct itself and precede all fields.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(magic = b"\x89PNG", byte_order = "be")]
+//! struct Header {
+//!     len: u32,
+//!     #[struct_deser(const = "0")]
+//!     reserved: u16,
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::TryFromBytes;
+//!
+//!     let header = Header::try_from_bytes(b"\x89PNG\0\0\0\x0d\0\0").unwrap();
+//!     assert_eq!(header, Header { len: 13, reserved: 0 });
+//!     assert!(Header::try_from_bytes(b"\x89PNG\0\0\0\x0d\0\x01").is_err());
+//! }
+//! ```
+//!
+//! # Padding
+//!
+//! Fields marked with `#[struct_deser(pad = N)]` (or `skip_bytes = N`) are preceded by `N` bytes of
+//! padding, which don't appear in the struct. Padding at the end of the struct is specified on the
+//! struct itself. The padding is filled with zeros, or with the byte given by `fill = B`, and
+//! ignored during deserialization unless `verify_pad` is specified too.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(byte_order = "le", pad = 2)]
+//! struct Entry {
+//!     kind: u8,
+//!     #[struct_deser(pad = 3, fill = 0xff, verify_pad)]
+//!     value: u32,
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::{IntoBytes, SerializedByteLen};
+//!
+//!     let mut bytes = [0; Entry::BYTE_LEN];
+//!     Entry { kind: 1, value: 2 }.into_bytes(&mut bytes);
+//!     assert_eq!(bytes, [1, 0xff, 0xff, 0xff, 2, 0, 0, 0, 0, 0]);
+//! }
+//! ```
+//!
+//! # Offsets
+//!
+//! Instead of counting padding, the offset of a field can be given using
+//! `#[struct_deser(offset = N)]`. The gap between the preceding fields and the offset is filled
+//! like padding, so `fill = B` and `verify_pad` apply to it too. Offsets overlapping preceding
+//! fields are rejected at compile time, as is the struct-level `size = N` not matching `BYTE_LEN`.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(byte_order = "le", size = 0x08)]
+//! struct Registers {
+//!     id: u8,
+//!     #[struct_deser(offset = 0x04)]
+//!     status: u32,
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::{IntoBytes, SerializedByteLen};
+//!
+//!     let mut bytes = [0; Registers::BYTE_LEN];
+//!     Registers { id: 1, status: 2 }.into_bytes(&mut bytes);
+//!     assert_eq!(bytes, [1, 0, 0, 0, 2, 0, 0, 0]);
+//! }
+//! ```
+//!
+//! # Bit fields
+//!
+//! Consecutive fields marked with `#[bits = N]` are packed into a group of bytes, each occupying
+//! `N` bits. By default the first field occupies the most significant bits of the group (as is
+//! usual in network protocols); `#[struct_deser(bit_order = "lsb")]` packs the first field into
+//! the least significant bits of the group read as little endian integer instead. The types of
+//! the fields must implement `BitField` and the groups must end on byte boundary.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! struct Ipv4Start {
+//!     #[bits = 4]
+//!     version: u8,
+//!     #[bits = 4]
+//!     ihl: u8,
+//!     #[bits = 6]
+//!     dscp: u8,
+//!     #[bits = 2]
+//!     ecn: u8,
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::{FromBytes, SerializedByteLen};
+//!
+//!     assert_eq!(Ipv4Start::BYTE_LEN, 2);
+//!     let start = Ipv4Start::from_bytes(&[0x45, 0x02]);
+//!     assert_eq!(start, Ipv4Start { version: 4, ihl: 5, dscp: 0, ecn: 2 });
+//! }
+//! ```
+//!
+//! # Views
+//!
+//! Structs of fixed length marked with `#[struct_deser(view)]` get `FooView<'a>` and
+//! `FooViewMut<'a>` types wrapping serialized bytes without copying them. Their methods named
+//! after the fields decode the fields on each access and `set_` methods encode them in place.
+//! Invalid values cause panics just like `FromBytes` and `IntoBytes` do. The views are created
+//! using `Foo::view()` and `Foo::view_mut()` and can be converted into `Foo` using `From`.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(view, byte_order = "be")]
+//! struct Header {
+//!     id: u16,
+//!     flags: u8,
+//! }
+//!
+//! fn main() {
+//!     let mut bytes = [0, 42, 1];
+//!     Header::view_mut(&mut bytes).set_flags(3);
+//!
+//!     let view = Header::view(&bytes);
+//!     assert_eq!(view.id(), 42);
+//!     assert_eq!(Header::from(view), Header { id: 42, flags: 3 });
+//! }
+//! ```
+//!
+//! # Owned arrays
+//!
+//! Structs and C-like enums without generic parameters implement `ToByteArray`, which converts
+//! them to and from arrays of exactly `BYTE_LEN` bytes, so no buffer has to be prepared.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! use struct_deser::ToByteArray;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(byte_order = "be")]
+//! struct Header {
+//!     id: u16,
+//!     flags: u8,
+//! }
+//!
+//! fn main() {
+//!     let header = Header { id: 42, flags: 3 };
+//!     let bytes = header.to_byte_array();
+//!
+//!     assert_eq!(bytes, [0, 42, 3]);
+//!     assert_eq!(Header::from_byte_array(&bytes), header);
+//! }
+//! ```
+//!
+//! Types in `byteorder` module additionally have `const fn` conversions.
+//!
+//! # Sequential access
+//!
+//! `ByteReader` and `ByteWriter` keep track of the offset when (de)serializing values one after
+//! another, which helps with implementing the traits by hand. The derived impls use them too.
+//!
+//! ```
+//! extern crate struct_deser;
+//!
+//! use struct_deser::byteorder::BE;
+//! use struct_deser::{ByteReader, ByteWriter};
+//!
+//! fn main() {
+//!     let mut bytes = [0; 4];
+//!     let mut writer = ByteWriter::new(&mut bytes);
+//!     writer.write(&42u8);
+//!     writer.skip(1);
+//!     writer.write_ordered::<u16, BE>(&0x0102);
+//!
+//!     let mut reader = ByteReader::new(&bytes);
+//!     assert_eq!(reader.read::<u8>(), 42);
+//!     reader.skip(1);
+//!     assert_eq!(reader.read_ordered::<u16, BE>(), 0x0102);
+//!     assert_eq!(reader.remaining(), 0);
+//! }
+//! ```
+//!
+//! # Records
+//!
+//! Buffers holding packed records of the same type can be iterated using `records()`, which yields
+//! either the deserialized values or their views. Views generated by `#[struct_deser(view)]` can
+//! also update the records in place using `records_mut()`. Untrusted input should be read using
+//! `try_records()`, which yields errors instead of panicking on invalid records.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(byte_order = "be", view)]
+//! struct Sample {
+//!     time: u16,
+//!     value: u8,
+//! }
+//!
+//! fn main() {
+//!     let mut capture = [0, 1, 10, 0, 2, 20];
+//!
+//!     for mut sample in struct_deser::records_mut::<SampleViewMut>(&mut capture).unwrap() {
+//!         let value = sample.value();
+//!         sample.set_value(value + 1);
+//!     }
+//!
+//!     let samples = struct_deser::records::<Sample>(&capture).unwrap();
+//!     assert_eq!(samples.len(), 2);
+//!     assert_eq!(samples.get(1), Some(Sample { time: 2, value: 21 }));
+//!
+//!     // trailing partial record
+//!     assert!(struct_deser::records::<Sample>(&capture[..4]).is_err());
+//! }
+//! ```
+//!
+//! # Readers and writers
+//!
+//! With `std` feature enabled, `io::FromReader` and `io::IntoWriter` provide `read_from` and
+//! `write_to` methods for all types implementing `TryFromBytes` and `IntoBytes`. Invalid data
+//! is reported as `std::io::ErrorKind::InvalidData`. Values up to 256 bytes long use a buffer on
+//! stack.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(byte_order = "be")]
+//! struct Header {
+//!     id: u16,
+//!     flags: u8,
+//! }
+//!
+//! # #[cfg(feature = "std")]
+//! fn main() {
+//!     use struct_deser::io::{FromReader, IntoWriter};
+//!
+//!     let mut file = Vec::new();
+//!     Header { id: 42, flags: 3 }.write_to(&mut file).unwrap();
+//!
+//!     let header = Header::read_from(&file[..]).unwrap();
+//!     assert_eq!(header, Header { id: 42, flags: 3 });
+//! }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+//!
+//! # Layout introspection
+//!
+//! Structs of fixed length get inherent `FIELDS` describing each field using `FieldInfo` and
+//! `OFFSET_<NAME>` consts holding the offsets of the fields. Bit fields share the offset of their
+//! group.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser)]
+//! #[struct_deser(byte_order = "be")]
+//! struct Header {
+//!     id: u16,
+//!     flags: u8,
+//! }
+//!
+//! fn main() {
+//!     assert_eq!(Header::OFFSET_FLAGS, 2);
+//!     for field in Header::FIELDS {
+//!         println!("{:#04x} {} ({} bytes)", field.offset, field.name, field.byte_len);
+//!     }
+//! }
+//! ```
+//!
+//! # Payload
+//!
+//! The last field of a struct can be `&'a [u8]`, in which case it borrows the rest of the input.
+//! Such structs implement `ParseBytes` and `WriteBytes` instead of the fixed-length traits and
+//! the length of the fields preceding the payload is available as `HEADER_LEN`.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! struct Datagram<'a> {
+//!     #[be]
+//!     port: u16,
+//!     payload: &'a [u8],
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::{ParseBytes, WriteBytes};
+//!
+//!     let (datagram, len) = Datagram::parse(&[0, 53, 1, 2, 3]).unwrap();
+//!     assert_eq!(datagram, Datagram { port: 53, payload: &[1, 2, 3] });
+//!     assert_eq!(len, 5);
+//!     assert_eq!(Datagram::HEADER_LEN, 2);
+//!     assert_eq!(datagram.serialized_len(), 5);
+//! }
+//! ```
+//!
+//! # Length stored in other field
+//!
+//! Fields of type `&'a [u8]` or `Vec<T>` marked with `#[struct_deser(len_from = "len")]` hold the
+//! number of bytes or elements stored in preceding field `len`, whose type must implement
+//! `Length`. When serializing, the value of `len` is ignored and the actual length is written
+//! instead, so the two can't disagree. Such structs implement `ParseBytes` and `WriteBytes` just
+//! like structs ending with payload.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(byte_order = "be")]
+//! struct Tlv<'a> {
+//!     kind: u16,
+//!     len: u16,
+//!     #[struct_deser(len_from = "len")]
+//!     value: &'a [u8],
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::ParseBytes;
+//!
+//!     let (tlv, len) = Tlv::parse(&[0, 1, 0, 2, 42, 47, 0]).unwrap();
+//!     assert_eq!(tlv.value, &[42, 47]);
+//!     assert_eq!(len, 6);
+//! }
+//! ```
+//!
+//! # Enums
+//!
+//! C-like enums are (de)serialized as their discriminant. The type of the discriminant on wire is
+//! specified using `#[struct_deser(repr = "u16")]` (or taken from `#[repr(u16)]`) and the byte
+//! order attributes of structs apply to it too. Deserializing an unknown value fails with
+//! `Error::UnknownDiscriminant` unless there's a variant marked with `#[struct_deser(other)]`
+//! holding the raw value.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(repr = "u16", byte_order = "be")]
+//! enum Opcode {
+//!     Read = 1,
+//!     Write = 2,
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::TryFromBytes;
+//!
+//!     assert_eq!(Opcode::try_from_bytes(&[0, 2]), Ok(Opcode::Write));
+//!     assert!(Opcode::try_from_bytes(&[0, 3]).is_err());
+//! }
+//! ```
+//!
+//! # Tagged enums
+//!
+//! Enums whose variants each hold a single type implementing `Identifier` are (de)serialized as
+//! the identifier of the variant followed by its value. They implement `ParseBytes` and
+//! `WriteBytes` because their length depends on the variant. The byte order of the identifier is
+//! specified using `#[struct_deser(byte_order = "...")]` on the enum. Using the same identifier
+//! for multiple variants is a compile error.
+//!
+//! ```
+//! extern crate struct_deser;
+//! #[macro_use]
+//! extern crate struct_deser_derive;
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(identifier = "1", identifier_type = "u8")]
+//! struct Ping {
+//!     #[be]
+//!     seq: u16,
+//! }
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! #[struct_deser(identifier = "2", identifier_type = "u8")]
+//! struct Data([u8; 4]);
+//!
+//! #[derive(StructDeser, Debug, Eq, PartialEq)]
+//! enum Message {
+//!     Ping(Ping),
+//!     Data(Data),
+//! }
+//!
+//! fn main() {
+//!     use struct_deser::ParseBytes;
+//!
+//!     let (message, len) = Message::parse(&[1, 0, 42]).unwrap();
+//!     assert_eq!(message, Message::Ping(Ping { seq: 42 }));
+//!     assert_eq!(len, 3);
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # extern crate struct_deser;
+//! # #[macro_use]
+//! # extern crate struct_deser_derive;
+//! #[derive(StructDeser)]
+//! #[struct_deser(identifier = "1", identifier_type = "u8")]
+//! struct Ping(u8);
+//!
+//! #[derive(StructDeser)]
+//! #[struct_deser(identifier = "1", identifier_type = "u8")]
+//! struct Pong(u8);
+//!
+//! // error: Variants `Ping` and `Pong` of `Message` have the same identifier
+//! #[derive(StructDeser)]
+//! enum Message {
+//!     Ping(Ping),
+//!     Pong(Pong),
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! # Diagnostics
+//!
+//! Misuse of the attributes is reported as a compile error pointing at the offending field or
+//! attribute, including options of `#[struct_deser(...)]` which aren't recognized.
+//!
+//! ```compile_fail
+//! # extern crate struct_deser;
+//! # #[macro_use]
+//! # extern crate struct_deser_derive;
+//! #[derive(StructDeser)]
+//! struct Header {
+//!     // error: Unknown field option `byte_ordr`
+//!     #[struct_deser(byte_ordr = "be")]
+//!     len: u8,
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ```compile_fail
+//! # extern crate struct_deser;
+//! # #[macro_use]
+//! # extern crate struct_deser_derive;
+//! #[derive(StructDeser)]
+//! struct Header {
+//!     // error: Conflicting byte order: byte order can be specified only once
+//!     #[be]
+//!     #[le]
+//!     len: u16,
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Fields holding integers or floats longer than one byte without any byte order additionally
+//! trigger a warning explaining what's missing, since the errors about unsatisfied trait bounds
+//! don't. The same applies to such identifiers of tagged enums.
+
+#![no_std]
+
+extern crate byteorder as byteorder_real;
+#[cfg(feature = "std")]
+extern crate std;
+
+use byteorder_real::ByteOrder;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::slice::{ChunksExact, ChunksExactMut};
+
+/// Re-exported essential items from `byteorder` crate and integers with fixed byte order.
+///
+/// The re-exports are intended mostly for `struct_deser-derive`. The wrapper types store the
+/// serialized bytes and implement plain `FromBytes` and `IntoBytes`, so they can be used without
+/// specifying byte order.
+pub mod byteorder {
+    pub use byteorder_real::ByteOrder;
+    pub use byteorder_real::NativeEndian as NE;
+    pub use byteorder_real::BE;
+    pub use byteorder_real::LE;
+
+    use super::{
+        check_buf_len, check_len, Error, FromBytes, IntoBytes, SerializedByteLen, ToByteArray,
+        TryFromBytes, TryIntoBytes,
+    };
+    use core::cmp::Ordering;
+    use core::fmt;
+
+    macro_rules! impl_endian_wrapper {
+        ($(#[$attr:meta])* $name:ident, $native:ty, $byte_len:expr, $order:ident, $read:ident, $write:ident) => {
+            $(#[$attr])*
+            #[derive(Copy, Clone, Default)]
+            #[repr(transparent)]
+            pub struct $name([u8; $byte_len]);
+
+            impl $name {
+                /// Creates the value from native value.
+                pub fn new(value: $native) -> Self {
+                    let mut bytes = [0; $byte_len];
+                    $order::$write(&mut bytes, value);
+                    $name(bytes)
+                }
+
+                /// Returns the native value.
+                pub fn get(self) -> $native {
+                    $order::$read(&self.0)
+                }
+
+                /// Creates the value from serialized bytes.
+                pub const fn from_byte_array(bytes: [u8; $byte_len]) -> Self {
+                    $name(bytes)
+                }
+
+                /// Returns the serialized bytes.
+                pub const fn to_byte_array(self) -> [u8; $byte_len] {
+                    self.0
+                }
+            }
+
+            impl ToByteArray for $name {
+                type Array = [u8; $byte_len];
+
+                fn to_byte_array(&self) -> Self::Array {
+                    self.0
+                }
+
+                fn from_byte_array(bytes: &Self::Array) -> Self {
+                    $name(*bytes)
+                }
+            }
+
+            impl From<$native> for $name {
+                fn from(value: $native) -> Self {
+                    $name::new(value)
+                }
+            }
+
+            impl From<$name> for $native {
+                fn from(value: $name) -> Self {
+                    value.get()
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.debug_tuple(stringify!($name)).field(&self.get()).finish()
+                }
+            }
+
+            impl PartialEq for $name {
+                fn eq(&self, other: &Self) -> bool {
+                    self.get() == other.get()
+                }
+            }
+
+            impl SerializedByteLen for $name {
+                const BYTE_LEN: usize = $byte_len;
+            }
+
+            impl FromBytes for $name {
+                fn from_bytes(bytes: &[u8]) -> Self {
+                    let mut buf = [0; $byte_len];
+                    buf.copy_from_slice(bytes);
+                    $name(buf)
+                }
+            }
+
+            impl IntoBytes for $name {
+                fn into_bytes(&self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.0)
+                }
+            }
+
+            impl TryFromBytes for $name {
+                fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+                    check_len::<Self>(bytes)?;
+                    Ok(Self::from_bytes(bytes))
+                }
+            }
+
+            impl TryIntoBytes for $name {
+                fn try_into_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+                    check_buf_len::<Self>(bytes)?;
+                    bytes[..$byte_len].copy_from_slice(&self.0);
+                    Ok($byte_len)
+                }
+            }
+        };
+    }
+
+    // Equal integers have equal bytes, so the bytes can be hashed.
+    macro_rules! impl_endian_integer {
+        ($name:ident, $native:ty, $byte_len:expr, $order:ident, $read:ident, $write:ident, $doc:expr) => {
+            impl_endian_wrapper!(
+                #[doc = $doc]
+                $name,
+                $native,
+                $byte_len,
+                $order,
+                $read,
+                $write
+            );
+
+            impl Eq for $name {}
+
+            impl PartialOrd for $name {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $name {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.get().cmp(&other.get())
+                }
+            }
+
+            impl core::hash::Hash for $name {
+                fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                    self.0.hash(state)
+                }
+            }
+        };
+    }
+
+    macro_rules! impl_endian_float {
+        ($name:ident, $native:ty, $byte_len:expr, $order:ident, $read:ident, $write:ident, $doc:expr) => {
+            impl_endian_wrapper!(
+                #[doc = $doc]
+                $name,
+                $native,
+                $byte_len,
+                $order,
+                $read,
+                $write
+            );
+
+            impl PartialOrd for $name {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    self.get().partial_cmp(&other.get())
+                }
+            }
+        };
+    }
+
+    impl_endian_integer!(U16Be, u16, 2, BE, read_u16, write_u16, "Big endian `u16`.");
+    impl_endian_integer!(
+        U16Le,
+        u16,
+        2,
+        LE,
+        read_u16,
+        write_u16,
+        "Little endian `u16`."
+    );
+    impl_endian_integer!(I16Be, i16, 2, BE, read_i16, write_i16, "Big endian `i16`.");
+    impl_endian_integer!(
+        I16Le,
+        i16,
+        2,
+        LE,
+        read_i16,
+        write_i16,
+        "Little endian `i16`."
+    );
+    impl_endian_integer!(U32Be, u32, 4, BE, read_u32, write_u32, "Big endian `u32`.");
+    impl_endian_integer!(
+        U32Le,
+        u32,
+        4,
+        LE,
+        read_u32,
+        write_u32,
+        "Little endian `u32`."
+    );
+    impl_endian_integer!(I32Be, i32, 4, BE, read_i32, write_i32, "Big endian `i32`.");
+    impl_endian_integer!(
+        I32Le,
+        i32,
+        4,
+        LE,
+        read_i32,
+        write_i32,
+        "Little endian `i32`."
+    );
+    impl_endian_integer!(U64Be, u64, 8, BE, read_u64, write_u64, "Big endian `u64`.");
+    impl_endian_integer!(
+        U64Le,
+        u64,
+        8,
+        LE,
+        read_u64,
+        write_u64,
+        "Little endian `u64`."
+    );
+    impl_endian_integer!(I64Be, i64, 8, BE, read_i64, write_i64, "Big endian `i64`.");
+    impl_endian_integer!(
+        I64Le,
+        i64,
+        8,
+        LE,
+        read_i64,
+        write_i64,
+        "Little endian `i64`."
+    );
+    impl_endian_integer!(
+        U128Be,
+        u128,
+        16,
+        BE,
+        read_u128,
+        write_u128,
+        "Big endian `u128`."
+    );
+    impl_endian_integer!(
+        U128Le,
+        u128,
+        16,
+        LE,
+        read_u128,
+        write_u128,
+        "Little endian `u128`."
+    );
+    impl_endian_integer!(
+        I128Be,
+        i128,
+        16,
+        BE,
+        read_i128,
+        write_i128,
+        "Big endian `i128`."
+    );
+    impl_endian_integer!(
+        I128Le,
+        i128,
+        16,
+        LE,
+        read_i128,
+        write_i128,
+        "Little endian `i128`."
+    );
+    impl_endian_float!(F32Be, f32, 4, BE, read_f32, write_f32, "Big endian `f32`.");
+    impl_endian_float!(
+        F32Le,
+        f32,
+        4,
+        LE,
+        read_f32,
+        write_f32,
+        "Little endian `f32`."
+    );
+    impl_endian_float!(F64Be, f64, 8, BE, read_f64, write_f64, "Big endian `f64`.");
+    impl_endian_float!(
+        F64Le,
+        f64,
+        8,
+        LE,
+        read_f64,
+        write_f64,
+        "Little endian `f64`."
+    );
+}
+
+/// Helpers for packing bit fields into bytes.
+/// This is intended mostly for `struct_deser-derive`.
+pub mod bits {
+    use byteorder_real::{ByteOrder, BE, LE};
+
+    /// Reads the group of bytes as an unsigned integer.
+    ///
+    /// The bytes are interpreted as big endian if `msb_first` is `true`, little endian otherwise.
+    pub fn read_group(bytes: &[u8], msb_first: bool) -> u128 {
+        if msb_first {
+            BE::read_uint128(bytes, bytes.len())
+        } else {
+            LE::read_uint128(bytes, bytes.len())
+        }
+    }
+
+    /// Writes the unsigned integer into the group of bytes.
+    ///
+    /// The bytes are written as big endian if `msb_first` is `true`, little endian otherwise.
+    pub fn write_group(bytes: &mut [u8], value: u128, msb_first: bool) {
+        let len = bytes.len();
+        if msb_first {
+            BE::write_uint128(bytes, value, len)
+        } else {
+            LE::write_uint128(bytes, value, len)
+        }
+    }
+}
+
+/// Ordered (de)serialization used by `struct_deser-derive` for fields with known byte order.
+///
+/// The traits are implemented for types implementing the ordered traits, arrays and types which
+/// don't depend on byte order, such as `u8` or `U16Be`. Those don't implement the public ordered
+/// traits, so that calling `from_bytes` on them isn't ambiguous. The derive implements the traits
+/// for all fixed-length types, so that they can be nested in structs with default byte order.
+#[doc(hidden)]
+pub mod ordered {
+    use super::{
+        check_buf_len, check_len, element, element_mut, try_array_from_fn, FromBytesOrdered,
+        IntoBytesOrdered, SerializedByteLen, TryFromBytesOrdered, TryIntoBytesOrdered,
+    };
+    use byteorder::*;
+    use byteorder_real::ByteOrder;
+    use core::marker::PhantomData;
+    use core::ops::Deref;
+    use Error;
+    use {U24, U40, U48, U56};
+
+    pub trait FromBytes: SerializedByteLen {
+        fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self;
+    }
+
+    pub trait IntoBytes: SerializedByteLen {
+        #[allow(clippy::wrong_self_convention)]
+        fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]);
+    }
+
+    pub trait TryFromBytes: SerializedByteLen + Sized {
+        fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error>;
+    }
+
+    pub trait TryIntoBytes: SerializedByteLen {
+        fn try_into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) -> Result<usize, Error>;
+    }
+
+    // Byte order is passed to the ordered traits
+    macro_rules! impl_dependent {
+        ($($type:ty),*) => {
+            $(
+                impl FromBytes for $type {
+                    fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self {
+                        <Self as FromBytesOrdered>::from_bytes::<BO>(bytes)
+                    }
+                }
+
+                impl IntoBytes for $type {
+                    fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) {
+                        <Self as IntoBytesOrdered>::into_bytes::<BO>(self, bytes)
+                    }
+                }
+
+                impl TryFromBytes for $type {
+                    fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error> {
+                        <Self as TryFromBytesOrdered>::try_from_bytes::<BO>(bytes)
+                    }
+                }
+
+                impl TryIntoBytes for $type {
+                    fn try_into_bytes<BO: ByteOrder>(
+                        &self,
+                        bytes: &mut [u8],
+                    ) -> Result<usize, Error> {
+                        <Self as TryIntoBytesOrdered>::try_into_bytes::<BO>(self, bytes)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_dependent!(u16, i16, u32, i32, u64, i64, u128, i128, f32, f64, U24, U40, U48, U56);
+
+    // Byte order is ignored
+    macro_rules! impl_independent {
+        ([$($generics:tt)*] $type:ty, $($rest:ty),+) => {
+            impl_independent!([$($generics)*] $type);
+            impl_independent!([$($generics)*] $($rest),+);
+        };
+        ([$($generics:tt)*] $type:ty) => {
+            impl<$($generics)*> FromBytes for $type {
+                fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self {
+                    <Self as ::FromBytes>::from_bytes(bytes)
+                }
+            }
+
+            impl<$($generics)*> IntoBytes for $type {
+                fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) {
+                    <Self as ::IntoBytes>::into_bytes(self, bytes)
+                }
+            }
+
+            impl<$($generics)*> TryFromBytes for $type {
+                fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error> {
+                    <Self as ::TryFromBytes>::try_from_bytes(bytes)
+                }
+            }
+
+            impl<$($generics)*> TryIntoBytes for $type {
+                fn try_into_bytes<BO: ByteOrder>(
+                    &self,
+                    bytes: &mut [u8],
+                ) -> Result<usize, Error> {
+                    <Self as ::TryIntoBytes>::try_into_bytes(self, bytes)
+                }
+            }
+        };
+    }
+
+    impl_independent!([] u8, i8);
+    impl_independent!(
+        [] U16Be, U16Le, I16Be, I16Le, U32Be, U32Le, I32Be, I32Le, U64Be, U64Le, I64Be, I64Le,
+        U128Be, U128Le, I128Be, I128Le, F32Be, F32Le, F64Be, F64Le
+    );
+
+    // Elements are (de)serialized using the same byte order, which they may ignore
+    impl<T: FromBytes, const N: usize> FromBytes for [T; N] {
+        fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self {
+            assert_eq!(bytes.len(), Self::BYTE_LEN);
+            core::array::from_fn(|index| T::from_bytes::<BO>(element::<T>(bytes, index)))
+        }
+    }
+
+    impl<T: IntoBytes, const N: usize> IntoBytes for [T; N] {
+        fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) {
+            assert_eq!(bytes.len(), Self::BYTE_LEN);
+            for (index, item) in self.iter().enumerate() {
+                item.into_bytes::<BO>(element_mut::<T>(bytes, index));
+            }
+        }
+    }
+
+    impl<T: TryFromBytes, const N: usize> TryFromBytes for [T; N] {
+        fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error> {
+            check_len::<Self>(bytes)?;
+            try_array_from_fn(|index| T::try_from_bytes::<BO>(element::<T>(bytes, index)))
+        }
+    }
+
+    impl<T: TryIntoBytes, const N: usize> TryIntoBytes for [T; N] {
+        fn try_into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+            check_buf_len::<Self>(bytes)?;
+            for (index, item) in self.iter().enumerate() {
+                item.try_into_bytes::<BO>(element_mut::<T>(bytes, index))?;
+            }
+            Ok(Self::BYTE_LEN)
+        }
+    }
+
+    /// Warns about numbers without byte order in places where the macro doesn't know their type.
+    ///
+    /// Calling `check` resolves to the deprecated inherent method for numbers longer than one
+    /// byte. Other types only have the method of `NoByteOrder` found through `Deref`.
+    pub struct MissingByteOrder<T>(pub NoByteOrder<T>);
+
+    pub struct NoByteOrder<T>(pub PhantomData<T>);
+
+    impl<T> NoByteOrder<T> {
+        pub fn check(&self) {}
+    }
+
+    impl<T> Deref for MissingByteOrder<T> {
+        type Target = NoByteOrder<T>;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    macro_rules! impl_missing_byte_order {
+        ($($type:ty),*) => {
+            $(
+                impl MissingByteOrder<$type> {
+                    #[deprecated(
+                        note = "Identifier of the enum has no byte order, specify it using `#[struct_deser(byte_order = \"...\")]`"
+                    )]
+                    pub fn check(&self) {}
+                }
+            )*
+        };
+    }
+
+    impl_missing_byte_order!(
+        u16, i16, u32, i32, u64, i64, u128, i128, f32, f64, U24, U40, U48, U56
+    );
+}
+
+/// Reading from `std::io::Read` and writing to `std::io::Write`.
+///
+/// Available with `std` feature. The traits are implemented for all types implementing
+/// `TryFromBytes` or `IntoBytes`, so importing them is enough to use the methods.
+#[cfg(feature = "std")]
+pub mod io {
+    use super::{IntoBytes, SerializedByteLen, TryFromBytes};
+    use std::io;
+
+    // Types longer than this are (de)serialized using buffer allocated on heap.
+    const STACK_BUF_LEN: usize = 256;
+
+    // Calls `f` with zeroed buffer of `T::BYTE_LEN` bytes
+    fn with_buf<T: SerializedByteLen, R, F: FnOnce(&mut [u8]) -> R>(f: F) -> R {
+        if T::BYTE_LEN <= STACK_BUF_LEN {
+            let mut buf = [0; STACK_BUF_LEN];
+            f(&mut buf[..T::BYTE_LEN])
+        } else {
+            f(&mut std::vec![0; T::BYTE_LEN])
+        }
+    }
+
+    /// Extension trait for deserializing values from readers.
+    pub trait FromReader: TryFromBytes {
+        /// Reads exactly `BYTE_LEN` bytes from `reader` and deserializes them.
+        ///
+        /// Bytes that can't be deserialized are reported as `io::ErrorKind::InvalidData`.
+        fn read_from<R: io::Read>(reader: R) -> io::Result<Self>;
+    }
+
+    impl<T: TryFromBytes> FromReader for T {
+        fn read_from<R: io::Read>(mut reader: R) -> io::Result<Self> {
+            with_buf::<T, _, _>(|buf| {
+                reader.read_exact(buf)?;
+                Ok(T::try_from_bytes(buf)?)
+            })
+        }
+    }
+
+    /// Extension trait for serializing values into writers.
+    pub trait IntoWriter: IntoBytes {
+        /// Serializes `self` and writes all `BYTE_LEN` bytes into `writer`.
+        fn write_to<W: io::Write>(&self, writer: W) -> io::Result<()>;
+    }
+
+    impl<T: IntoBytes> IntoWriter for T {
+        fn write_to<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+            with_buf::<T, _, _>(|buf| {
+                self.into_bytes(buf);
+                writer.write_all(buf)
+            })
+        }
+    }
+}
+
+/// Defines length (number of bytes) of struct when serialized.
+///
+/// It's used by other traits, so it guarantees that the sizes are same.
+pub trait SerializedByteLen {
+    /// How many bytes the struct occupies on wire.
+    const BYTE_LEN: usize;
+}
+
+/// Represents types that can be constructed from bytes.
+pub trait FromBytes: SerializedByteLen {
+    /// Creates `Self` by deserializing from bytes.
+    fn from_bytes(bytes: &[u8]) -> Self;
+}
+
+/// Represents types that can be serialized into bytes.
+pub trait IntoBytes: SerializedByteLen {
+    /// Serializes `self`.
+    /// This function must write to the provided slice.
+    #[allow(clippy::wrong_self_convention)]
+    fn into_bytes(&self, bytes: &mut [u8]);
+}
+
+/// Array of bytes with length known at compile time.
+///
+/// It's implemented for `[u8; N]` and used as the output of `ToByteArray`.
+pub trait ByteArray: AsRef<[u8]> + AsMut<[u8]> + Copy {
+    /// Returns the array filled with zeros.
+    fn zeroed() -> Self;
+}
+
+impl<const N: usize> ByteArray for [u8; N] {
+    fn zeroed() -> Self {
+        [0; N]
+    }
+}
+
+/// Represents types that can be serialized into an owned array, so that callers don't have to
+/// prepare a buffer.
+///
+/// The length of `Array` is `BYTE_LEN`, so the conversions can't fail on wrong length. It's
+/// derived for structs and C-like enums without generic parameters.
+pub trait ToByteArray: FromBytes + IntoBytes {
+    /// Array of `BYTE_LEN` bytes.
+    type Array: ByteArray;
+
+    /// Serializes `self` into a new array.
+    fn to_byte_array(&self) -> Self::Array {
+        let mut bytes = Self::Array::zeroed();
+        self.into_bytes(bytes.as_mut());
+        bytes
+    }
+
+    /// Creates `Self` by deserializing from an array.
+    fn from_byte_array(bytes: &Self::Array) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_bytes(bytes.as_ref())
+    }
+}
+
+/// Byte order chosen at runtime.
+///
+/// This is useful for formats declaring their byte order in a header. The `*_with` methods of
+/// ordered traits dispatch to the `BE` or `LE` implementation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Endian {
+    /// Most significant byte first.
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+impl Endian {
+    /// Byte order of the target platform.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Endian = Endian::Big;
+
+    /// Byte order of the target platform.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Endian = Endian::Little;
+}
+
+/// Represents types that can be constructed from bytes with specific endianess.
+pub trait FromBytesOrdered: SerializedByteLen {
+    /// Creates `Self` by deserializing from bytes using byte order.
+    fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self;
+
+    /// Creates `Self` by deserializing from bytes using byte order chosen at runtime.
+    fn from_bytes_with(bytes: &[u8], endian: Endian) -> Self
+    where
+        Self: Sized,
+    {
+        match endian {
+            Endian::Big => Self::from_bytes::<byteorder::BE>(bytes),
+            Endian::Little => Self::from_bytes::<byteorder::LE>(bytes),
+        }
+    }
+}
+
+/// Represents types that can be serialized into bytes with specific endianess.
+pub trait IntoBytesOrdered: SerializedByteLen {
+    /// Serializes `self` using byte order.
+    /// This function must write to the provided slice.
+    #[allow(clippy::wrong_self_convention)]
+    fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]);
+
+    /// Serializes `self` using byte order chosen at runtime.
+    /// This function must write to the provided slice.
+    #[allow(clippy::wrong_self_convention)]
+    fn into_bytes_with(&self, bytes: &mut [u8], endian: Endian) {
+        match endian {
+            Endian::Big => self.into_bytes::<byteorder::BE>(bytes),
+            Endian::Little => self.into_bytes::<byteorder::LE>(bytes),
+        }
+    }
+}
+
+/// Represents types that can be constructed from bytes without panicking on invalid input.
+pub trait TryFromBytes: SerializedByteLen + Sized {
+    /// Creates `Self` by deserializing from bytes.
+    /// Returns an error if the bytes can't be deserialized (e.g. their length is wrong).
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+/// Represents types that can be constructed from bytes with specific endianess without panicking
+/// on invalid input.
+pub trait TryFromBytesOrdered: SerializedByteLen + Sized {
+    /// Creates `Self` by deserializing from bytes using byte order.
+    /// Returns an error if the bytes can't be deserialized (e.g. their length is wrong).
+    fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error>;
+
+    /// Creates `Self` by deserializing from bytes using byte order chosen at runtime.
+    /// Returns an error if the bytes can't be deserialized (e.g. their length is wrong).
+    fn try_from_bytes_with(bytes: &[u8], endian: Endian) -> Result<Self, Error> {
+        match endian {
+            Endian::Big => Self::try_from_bytes::<byteorder::BE>(bytes),
+            Endian::Little => Self::try_from_bytes::<byteorder::LE>(bytes),
+        }
+    }
+}
+
+/// Represents types that can be serialized into a buffer which may be larger than needed.
+pub trait TryIntoBytes: SerializedByteLen {
+    /// Serializes `self` into the beginning of the provided slice.
+    /// Returns the number of bytes written or an error if the slice is too short.
+    fn try_into_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error>;
+}
+
+/// Represents types that can be serialized with specific endianess into a buffer which may be
+/// larger than needed.
+pub trait TryIntoBytesOrdered: SerializedByteLen {
+    /// Serializes `self` into the beginning of the provided slice using byte order.
+    /// Returns the number of bytes written or an error if the slice is too short.
+    fn try_into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) -> Result<usize, Error>;
+
+    /// Serializes `self` into the beginning of the provided slice using byte order chosen at
+    /// runtime.
+    /// Returns the number of bytes written or an error if the slice is too short.
+    fn try_into_bytes_with(&self, bytes: &mut [u8], endian: Endian) -> Result<usize, Error> {
+        match endian {
+            Endian::Big => self.try_into_bytes::<byteorder::BE>(bytes),
+            Endian::Little => self.try_into_bytes::<byteorder::LE>(bytes),
+        }
+    }
+}
+
+/// Represents types that can be parsed from the beginning of bytes without knowing their length in
+/// advance.
+///
+/// It's implemented for all types implementing `TryFromBytes` and derived for tagged enums.
+pub trait ParseBytes<'a>: Sized {
+    /// Parses `Self` from the beginning of the provided slice.
+    /// Returns the value and the number of bytes consumed.
+    fn parse(bytes: &'a [u8]) -> Result<(Self, usize), Error>;
+}
+
+/// Represents types that can be serialized without knowing their length in advance.
+///
+/// It's implemented for all types implementing `TryIntoBytes` and derived for tagged enums.
+pub trait WriteBytes {
+    /// Returns the number of bytes `self` occupies on wire.
+    fn serialized_len(&self) -> usize;
+
+    /// Serializes `self` into the beginning of the provided slice.
+    /// Returns the number of bytes written or an error if the slice is too short.
+    fn write_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error>;
+}
+
+/// Description of a field in the serialized layout of a struct.
+///
+/// The derive generates `FIELDS` with descriptions of all fields of structs with fixed length.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FieldInfo {
+    /// Name of the field, or its index in tuple structs.
+    pub name: &'static str,
+    /// Offset of the first byte of the field.
+    pub offset: usize,
+    /// Number of bytes occupied by the field, or by the whole group of bit fields.
+    pub byte_len: usize,
+    /// Byte order used for the field.
+    pub byte_order: FieldByteOrder,
+    /// The type of the field as written in the struct.
+    pub type_name: &'static str,
+    /// Position of bit field within its group.
+    pub bits: Option<BitRange>,
+}
+
+/// Byte order used for (de)serializing a field.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FieldByteOrder {
+    /// The type of the field doesn't depend on byte order.
+    None,
+    /// Big endian.
+    Big,
+    /// Little endian.
+    Little,
+    /// Byte order of the target platform.
+    Native,
+    /// The byte order is chosen by the caller of ordered traits.
+    Inherited,
+}
+
+/// Position of bit field within the integer made of its group.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BitRange {
+    /// Number of bits less significant than the field.
+    pub shift: u32,
+    /// Number of bits occupied by the field.
+    pub width: u32,
+}
+
+/// Error returned when (de)serialization fails.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Error {
+    /// The input doesn't have the length required by the type.
+    InvalidLength {
+        /// Number of bytes the type occupies on wire.
+        expected: usize,
+        /// Number of bytes actually provided.
+        actual: usize,
+        /// The first field which didn't fit into provided bytes, if known.
+        field: Option<&'static str>,
+    },
+    /// The output buffer is too short to hold the serialized value.
+    BufferTooSmall {
+        /// Number of bytes the value occupies on wire.
+        required: usize,
+        /// Length of the provided buffer.
+        actual: usize,
+    },
+    /// The bytes don't represent any variant of an enum.
+    UnknownDiscriminant {
+        /// Name of the enum.
+        type_name: &'static str,
+        /// The field holding the enum, if known.
+        field: Option<&'static str>,
+    },
+    /// The identifier doesn't match any variant of a tagged enum.
+    UnknownIdentifier {
+        /// Name of the enum.
+        type_name: &'static str,
+        /// The field holding the enum, if known.
+        field: Option<&'static str>,
+    },
+    /// The value doesn't fit into its serialized representation.
+    OutOfRange {
+        /// The field holding the value, if known.
+        field: Option<&'static str>,
+    },
+    /// Magic bytes, constant field or verified padding don't have the expected value.
+    InvalidConstant {
+        /// Name of the type containing the constant.
+        type_name: &'static str,
+        /// The constant field, `None` for magic bytes and padding which don't belong to any field.
+        field: Option<&'static str>,
+    },
+}
+
+impl Error {
+    /// Attaches the name of the field in which the error occurred.
+    ///
+    /// If the error already refers to a (more nested) field, it's returned unchanged.
+    pub fn in_field(self, name: &'static str) -> Self {
+        match self {
+            Error::InvalidLength {
+                expected,
+                actual,
+                field: None,
+            } => Error::InvalidLength {
+                expected,
+                actual,
+                field: Some(name),
+            },
+            Error::UnknownDiscriminant {
+                type_name,
+                field: None,
+            } => Error::UnknownDiscriminant {
+                type_name,
+                field: Some(name),
+            },
+            Error::UnknownIdentifier {
+                type_name,
+                field: None,
+            } => Error::UnknownIdentifier {
+                type_name,
+                field: Some(name),
+            },
+            Error::OutOfRange { field: None } => Error::OutOfRange { field: Some(name) },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidLength {
+                expected,
+                actual,
+                field: Some(field),
+            } => write!(
+                f,
+                "invalid length: expected {} bytes, got {} (field `{}` doesn't fit)",
+                expected, actual, field
+            ),
+            Error::InvalidLength {
+                expected,
+                actual,
+                field: None,
+            } => write!(
+                f,
+                "invalid length: expected {} bytes, got {}",
+                expected, actual
+            ),
+            Error::BufferTooSmall { required, actual } => write!(
+                f,
+                "buffer too small: {} bytes required, got {}",
+                required, actual
+            ),
+            Error::UnknownDiscriminant {
+                type_name,
+                field: Some(field),
+            } => write!(f, "unknown variant of {} in field `{}`", type_name, field),
+            Error::UnknownDiscriminant {
+                type_name,
+                field: None,
+            } => write!(f, "unknown variant of {}", type_name),
+            Error::UnknownIdentifier {
+                type_name,
+                field: Some(field),
+            } => write!(
+                f,
+                "unknown identifier of {} in field `{}`",
+                type_name, field
+            ),
+            Error::UnknownIdentifier {
+                type_name,
+                field: None,
+            } => write!(f, "unknown identifier of {}", type_name),
+            Error::OutOfRange { field: Some(field) } => {
+                write!(f, "value of field `{}` is out of range", field)
+            }
+            Error::OutOfRange { field: None } => write!(f, "value is out of range"),
+            Error::InvalidConstant {
+                type_name,
+                field: Some(field),
+            } => write!(
+                f,
+                "invalid value of constant field `{}` of {}",
+                field, type_name
+            ),
+            Error::InvalidConstant {
+                type_name,
+                field: None,
+            } => write!(f, "invalid magic bytes or padding of {}", type_name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Checks that `bytes` have exactly the length of `T`.
+fn check_len<T: SerializedByteLen>(bytes: &[u8]) -> Result<(), Error> {
+    if bytes.len() == T::BYTE_LEN {
+        Ok(())
+    } else {
+        Err(Error::InvalidLength {
+            expected: T::BYTE_LEN,
+            actual: bytes.len(),
+            field: None,
+        })
+    }
+}
+
+/// Checks that `bytes` are long enough to hold serialized `T`.
+fn check_buf_len<T: SerializedByteLen>(bytes: &[u8]) -> Result<(), Error> {
+    if bytes.len() >= T::BYTE_LEN {
+        Ok(())
+    } else {
+        Err(Error::BufferTooSmall {
+            required: T::BYTE_LEN,
+            actual: bytes.len(),
+        })
+    }
+}
+
+impl<'a, T: TryFromBytes> ParseBytes<'a> for T {
+    fn parse(bytes: &'a [u8]) -> Result<(Self, usize), Error> {
+        if bytes.len() < T::BYTE_LEN {
+            return Err(Error::InvalidLength {
+                expected: T::BYTE_LEN,
+                actual: bytes.len(),
+                field: None,
+            });
+        }
+
+        T::try_from_bytes(&bytes[..T::BYTE_LEN]).map(|value| (value, T::BYTE_LEN))
+    }
+}
+
+impl<T: TryIntoBytes> WriteBytes for T {
+    fn serialized_len(&self) -> usize {
+        T::BYTE_LEN
+    }
+
+    fn write_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+        self.try_into_bytes(bytes)
+    }
+}
+
+/// Cursor deserializing values from a byte slice one after another.
+///
+/// This is useful for implementing the traits by hand and it's also used by
+/// `struct_deser-derive`. The infallible methods panic if there aren't enough bytes remaining, the
+/// `try_` variants return an error instead. The reader is not advanced if reading fails.
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Creates the reader starting at the beginning of `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        ByteReader { bytes, position: 0 }
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes that weren't read yet.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Returns the bytes that weren't read yet without advancing the reader.
+    pub fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.position..]
+    }
+
+    // Returns next `len` bytes without advancing the reader
+    fn peek(&self, len: usize) -> Result<&'a [u8], Error> {
+        if self.remaining() < len {
+            return Err(Error::InvalidLength {
+                expected: self.position.saturating_add(len),
+                actual: self.bytes.len(),
+                field: None,
+            });
+        }
+
+        Ok(&self.bytes[self.position..(self.position + len)])
+    }
+
+    /// Reads next `len` bytes.
+    pub fn try_read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self.peek(len)?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    /// Skips next `len` bytes.
+    pub fn try_skip(&mut self, len: usize) -> Result<(), Error> {
+        self.try_read_bytes(len).map(drop)
+    }
+
+    /// Deserializes the next value.
+    pub fn try_read<T: TryFromBytes>(&mut self) -> Result<T, Error> {
+        let value = T::try_from_bytes(self.peek(T::BYTE_LEN)?)?;
+        self.position += T::BYTE_LEN;
+        Ok(value)
+    }
+
+    /// Deserializes the next value using byte order `BO`.
+    pub fn try_read_ordered<T: TryFromBytesOrdered, BO: ByteOrder>(&mut self) -> Result<T, Error> {
+        let value = T::try_from_bytes::<BO>(self.peek(T::BYTE_LEN)?)?;
+        self.position += T::BYTE_LEN;
+        Ok(value)
+    }
+
+    /// Reads next `len` bytes.
+    pub fn read_bytes(&mut self, len: usize) -> &'a [u8] {
+        match self.try_read_bytes(len) {
+            Ok(bytes) => bytes,
+            Err(err) => panic!("failed to read bytes: {}", err),
+        }
+    }
+
+    /// Skips next `len` bytes.
+    pub fn skip(&mut self, len: usize) {
+        if let Err(err) = self.try_skip(len) {
+            panic!("failed to skip bytes: {}", err);
+        }
+    }
+
+    /// Deserializes the next value.
+    pub fn read<T: FromBytes>(&mut self) -> T {
+        T::from_bytes(self.read_bytes(T::BYTE_LEN))
+    }
+
+    /// Deserializes the next value using byte order `BO`.
+    pub fn read_ordered<T: FromBytesOrdered, BO: ByteOrder>(&mut self) -> T {
+        T::from_bytes::<BO>(self.read_bytes(T::BYTE_LEN))
+    }
+}
+
+/// Cursor serializing values into a byte slice one after another.
+///
+/// This is the counterpart of `ByteReader`. The infallible methods panic if there isn't enough
+/// space remaining, the `try_` variants return an error instead.
+#[derive(Debug)]
+pub struct ByteWriter<'a> {
+    bytes: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> ByteWriter<'a> {
+    /// Creates the writer starting at the beginning of `bytes`.
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        ByteWriter { bytes, position: 0 }
+    }
+
+    /// Returns the number of bytes written (or skipped) so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes that can still be written.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    // Returns next `len` bytes without advancing the writer
+    fn next(&mut self, len: usize) -> Result<&mut [u8], Error> {
+        if self.remaining() < len {
+            return Err(Error::BufferTooSmall {
+                required: self.position.saturating_add(len),
+                actual: self.bytes.len(),
+            });
+        }
+
+        Ok(&mut self.bytes[self.position..(self.position + len)])
+    }
+
+    /// Copies `bytes` into the output.
+    pub fn try_write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.next(bytes.len())?.copy_from_slice(bytes);
+        self.position += bytes.len();
+        Ok(())
+    }
+
+    /// Sets next `len` bytes to `byte`.
+    pub fn try_fill(&mut self, len: usize, byte: u8) -> Result<(), Error> {
+        self.next(len)?.fill(byte);
+        self.position += len;
+        Ok(())
+    }
+
+    /// Skips next `len` bytes, leaving them unchanged.
+    pub fn try_skip(&mut self, len: usize) -> Result<(), Error> {
+        self.next(len)?;
+        self.position += len;
+        Ok(())
+    }
+
+    /// Passes next `len` bytes to `f`, which serializes a value into them.
+    ///
+    /// The writer is advanced only if `f` succeeds.
+    pub fn try_write_with<T, F: FnOnce(&mut [u8]) -> Result<T, Error>>(
+        &mut self,
+        len: usize,
+        f: F,
+    ) -> Result<T, Error> {
+        let result = f(self.next(len)?)?;
+        self.position += len;
+        Ok(result)
+    }
+
+    /// Serializes the value.
+    pub fn try_write<T: TryIntoBytes + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.try_into_bytes(self.next(T::BYTE_LEN)?)?;
+        self.position += T::BYTE_LEN;
+        Ok(())
+    }
+
+    /// Serializes the value using byte order `BO`.
+    pub fn try_write_ordered<T: TryIntoBytesOrdered + ?Sized, BO: ByteOrder>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.try_into_bytes::<BO>(self.next(T::BYTE_LEN)?)?;
+        self.position += T::BYTE_LEN;
+        Ok(())
+    }
+
+    /// Copies `bytes` into the output.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        if let Err(err) = self.try_write_bytes(bytes) {
+            panic!("failed to write bytes: {}", err);
+        }
+    }
 
-#![no_std]
+    /// Sets next `len` bytes to `byte`.
+    pub fn fill(&mut self, len: usize, byte: u8) {
+        if let Err(err) = self.try_fill(len, byte) {
+            panic!("failed to fill bytes: {}", err);
+        }
+    }
 
-extern crate byteorder as byteorder_real;
+    /// Skips next `len` bytes, leaving them unchanged.
+    pub fn skip(&mut self, len: usize) {
+        if let Err(err) = self.try_skip(len) {
+            panic!("failed to skip bytes: {}", err);
+        }
+    }
 
-use byteorder_real::ByteOrder;
+    /// Passes next `len` bytes to `f`, which serializes a value into them.
+    pub fn write_with<T, F: FnOnce(&mut [u8]) -> T>(&mut self, len: usize, f: F) -> T {
+        let result = match self.next(len) {
+            Ok(bytes) => f(bytes),
+            Err(err) => panic!("failed to write value: {}", err),
+        };
+        self.position += len;
+        result
+    }
 
-/// Re-exported essential items from `byteorder` crate.
-/// This is intended mostly for `struct_deser-derive`.
-pub mod byteorder {
-    pub use byteorder_real::ByteOrder;
-    pub use byteorder_real::BE;
-    pub use byteorder_real::LE;
+    /// Serializes the value.
+    pub fn write<T: IntoBytes + ?Sized>(&mut self, value: &T) {
+        let len = T::BYTE_LEN;
+        match self.next(len) {
+            Ok(bytes) => value.into_bytes(bytes),
+            Err(err) => panic!("failed to write value: {}", err),
+        }
+        self.position += len;
+    }
+
+    /// Serializes the value using byte order `BO`.
+    pub fn write_ordered<T: IntoBytesOrdered + ?Sized, BO: ByteOrder>(&mut self, value: &T) {
+        let len = T::BYTE_LEN;
+        match self.next(len) {
+            Ok(bytes) => value.into_bytes::<BO>(bytes),
+            Err(err) => panic!("failed to write value: {}", err),
+        }
+        self.position += len;
+    }
 }
 
-/// Defines length (number of bytes) of struct when serialized.
+/// Types which can be created from a record in a buffer of packed records.
 ///
-/// It's used by other traits, so it guarantees that the sizes are same.
-pub trait SerializedByteLen {
-    /// How many bytes the struct occupies on wire.
-    const BYTE_LEN: usize;
+/// It's implemented for all types implementing `FromBytes` and for views generated by
+/// `#[struct_deser(view)]`.
+pub trait FromRecord<'a>: Sized {
+    /// Length of each record in bytes.
+    const RECORD_LEN: usize;
+
+    /// Creates the value from a record.
+    ///
+    /// The length of `bytes` must be `RECORD_LEN`.
+    fn from_record(bytes: &'a [u8]) -> Self;
 }
 
-/// Represents types that can be constructed from bytes.
-pub trait FromBytes: SerializedByteLen {
-    /// Creates `Self` by deserializing from bytes.
-    fn from_bytes(bytes: &[u8]) -> Self;
+impl<'a, T: FromBytes> FromRecord<'a> for T {
+    const RECORD_LEN: usize = T::BYTE_LEN;
+
+    fn from_record(bytes: &'a [u8]) -> Self {
+        T::from_bytes(bytes)
+    }
 }
 
-/// Represents types that can be serialized into bytes.
-pub trait IntoBytes: SerializedByteLen {
-    /// Serializes `self`.
-    /// This function must write to the provided slice.
-    fn into_bytes(&self, bytes: &mut [u8]);
+/// Types which can update a record in a buffer of packed records in place.
+///
+/// It's implemented for mutable views generated by `#[struct_deser(view)]`.
+pub trait FromRecordMut<'a>: Sized {
+    /// Length of each record in bytes.
+    const RECORD_LEN: usize;
+
+    /// Creates the value from a record.
+    ///
+    /// The length of `bytes` must be `RECORD_LEN`.
+    fn from_record_mut(bytes: &'a mut [u8]) -> Self;
 }
 
-/// Represents types that can be constructed from bytes with specific endianess.
-pub trait FromBytesOrdered: SerializedByteLen {
-    /// Creates `Self` by deserializing from bytes using byte order.
-    fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self;
+/// Checks that `len` bytes hold whole records of `record_len` bytes.
+fn check_records(len: usize, record_len: usize) -> Result<(), Error> {
+    assert_ne!(record_len, 0, "records must not be empty");
+
+    match len % record_len {
+        0 => Ok(()),
+        partial => Err(Error::InvalidLength {
+            expected: len - partial + record_len,
+            actual: len,
+            field: None,
+        }),
+    }
 }
 
-/// Represents types that can be serialized into bytes with specific endianess.
-pub trait IntoBytesOrdered: SerializedByteLen {
-    /// Serializes `self` using byte order.
-    /// This function must write to the provided slice.
-    fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]);
+/// Iterates over packed records of type `T`, such as `Foo` or `FooView`.
+///
+/// Returns an error if `bytes` end with a partial record.
+///
+/// # Panics
+///
+/// This panics if the length of `T` is zero. Records deserialized using `FromBytes` panic when
+/// yielded if they are invalid (e.g. have wrong magic bytes or unknown discriminant), use
+/// `try_records()` for untrusted input.
+pub fn records<'a, T: FromRecord<'a>>(bytes: &'a [u8]) -> Result<Records<'a, T>, Error> {
+    check_records(bytes.len(), T::RECORD_LEN)?;
+
+    Ok(Records {
+        bytes,
+        marker: PhantomData,
+    })
+}
+
+/// Iterates over packed records, deserializing each of them using `TryFromBytes`.
+///
+/// Returns an error if `bytes` end with a partial record. Invalid records are yielded as errors,
+/// so the remaining records can still be read.
+///
+/// # Panics
+///
+/// This panics if the length of `T` is zero.
+pub fn try_records<T: TryFromBytes>(bytes: &[u8]) -> Result<TryRecords<'_, T>, Error> {
+    check_records(bytes.len(), T::BYTE_LEN)?;
+
+    Ok(TryRecords {
+        chunks: bytes.chunks_exact(T::BYTE_LEN),
+        marker: PhantomData,
+    })
+}
+
+/// Iterates over packed records mutably, yielding mutable views.
+///
+/// Returns an error if `bytes` end with a partial record.
+///
+/// # Panics
+///
+/// This panics if the length of `T` is zero.
+pub fn records_mut<'a, T: FromRecordMut<'a>>(
+    bytes: &'a mut [u8],
+) -> Result<RecordsMut<'a, T>, Error> {
+    check_records(bytes.len(), T::RECORD_LEN)?;
+
+    Ok(RecordsMut {
+        chunks: bytes.chunks_exact_mut(T::RECORD_LEN),
+        marker: PhantomData,
+    })
+}
+
+/// Iterator over packed records returned by `records()`.
+#[derive(Debug)]
+pub struct Records<'a, T> {
+    bytes: &'a [u8],
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: FromRecord<'a>> Records<'a, T> {
+    /// Returns the record at `index` counting from the current position.
+    pub fn get(&self, index: usize) -> Option<T> {
+        let start = index.checked_mul(T::RECORD_LEN)?;
+        let bytes = self.bytes.get(start..)?.get(..T::RECORD_LEN)?;
+        Some(T::from_record(bytes))
+    }
+
+    /// Returns the bytes of remaining records.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a, T> Clone for Records<'a, T> {
+    fn clone(&self) -> Self {
+        Records {
+            bytes: self.bytes,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: FromRecord<'a>> Iterator for Records<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let (record, rest) = self.bytes.split_at(T::RECORD_LEN);
+        self.bytes = rest;
+        Some(T::from_record(record))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bytes.len() / T::RECORD_LEN;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let skip = n.saturating_mul(T::RECORD_LEN).min(self.bytes.len());
+        self.bytes = &self.bytes[skip..];
+        self.next()
+    }
+}
+
+impl<'a, T: FromRecord<'a>> DoubleEndedIterator for Records<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let (rest, record) = self.bytes.split_at(self.bytes.len() - T::RECORD_LEN);
+        self.bytes = rest;
+        Some(T::from_record(record))
+    }
+}
+
+impl<'a, T: FromRecord<'a>> ExactSizeIterator for Records<'a, T> {}
+
+impl<'a, T: FromRecord<'a>> FusedIterator for Records<'a, T> {}
+
+/// Iterator over packed records returned by `try_records()`.
+///
+/// Random access is available through `Iterator::nth`.
+#[derive(Debug)]
+pub struct TryRecords<'a, T> {
+    chunks: ChunksExact<'a, u8>,
+    marker: PhantomData<fn() -> T>,
 }
 
+impl<'a, T> Clone for TryRecords<'a, T> {
+    fn clone(&self) -> Self {
+        TryRecords {
+            chunks: self.chunks.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: TryFromBytes> Iterator for TryRecords<'a, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(T::try_from_bytes)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.chunks.nth(n).map(T::try_from_bytes)
+    }
+}
+
+impl<'a, T: TryFromBytes> DoubleEndedIterator for TryRecords<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(T::try_from_bytes)
+    }
+}
+
+impl<'a, T: TryFromBytes> ExactSizeIterator for TryRecords<'a, T> {}
+
+impl<'a, T: TryFromBytes> FusedIterator for TryRecords<'a, T> {}
+
+/// Iterator over packed records returned by `records_mut()`.
+///
+/// Random access is available through `Iterator::nth`.
+#[derive(Debug)]
+pub struct RecordsMut<'a, T> {
+    chunks: ChunksExactMut<'a, u8>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: FromRecordMut<'a>> Iterator for RecordsMut<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(T::from_record_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.chunks.nth(n).map(T::from_record_mut)
+    }
+}
+
+impl<'a, T: FromRecordMut<'a>> DoubleEndedIterator for RecordsMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(T::from_record_mut)
+    }
+}
+
+impl<'a, T: FromRecordMut<'a>> ExactSizeIterator for RecordsMut<'a, T> {}
+
+impl<'a, T: FromRecordMut<'a>> FusedIterator for RecordsMut<'a, T> {}
+
 macro_rules! impl_from_into_bytes {
     ($type:ty, $byte_len:expr, $from:ident, $into:ident) => {
         impl SerializedByteLen for $type {
@@ -110,6 +2079,21 @@ macro_rules! impl_from_into_bytes {
                 BO::$into(bytes, *self)
             }
         }
+
+        impl TryFromBytesOrdered for $type {
+            fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error> {
+                check_len::<Self>(bytes)?;
+                Ok(BO::$from(bytes))
+            }
+        }
+
+        impl TryIntoBytesOrdered for $type {
+            fn try_into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+                check_buf_len::<Self>(bytes)?;
+                BO::$into(&mut bytes[..$byte_len], *self);
+                Ok($byte_len)
+            }
+        }
     };
 }
 
@@ -129,6 +2113,21 @@ impl IntoBytes for u8 {
     }
 }
 
+impl TryIntoBytes for u8 {
+    fn try_into_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+        check_buf_len::<Self>(bytes)?;
+        bytes[0] = *self;
+        Ok(1)
+    }
+}
+
+impl TryFromBytes for u8 {
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_len::<Self>(bytes)?;
+        Ok(bytes[0])
+    }
+}
+
 impl SerializedByteLen for i8 {
     const BYTE_LEN: usize = 1;
 }
@@ -145,16 +2144,230 @@ impl IntoBytes for i8 {
     }
 }
 
+impl TryIntoBytes for i8 {
+    fn try_into_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+        check_buf_len::<Self>(bytes)?;
+        bytes[0] = *self as u8;
+        Ok(1)
+    }
+}
+
+impl TryFromBytes for i8 {
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_len::<Self>(bytes)?;
+        Ok(bytes[0] as i8)
+    }
+}
+
+impl ToByteArray for u8 {
+    type Array = [u8; 1];
+}
+
+impl ToByteArray for i8 {
+    type Array = [u8; 1];
+}
+
 impl_from_into_bytes!(u16, 2, read_u16, write_u16);
 impl_from_into_bytes!(i16, 2, read_i16, write_i16);
 impl_from_into_bytes!(u32, 4, read_u32, write_u32);
 impl_from_into_bytes!(i32, 4, read_i32, write_i32);
 impl_from_into_bytes!(u64, 8, read_u64, write_u64);
 impl_from_into_bytes!(i64, 8, read_i64, write_i64);
+impl_from_into_bytes!(u128, 16, read_u128, write_u128);
+impl_from_into_bytes!(i128, 16, read_i128, write_i128);
+impl_from_into_bytes!(f32, 4, read_f32, write_f32);
+impl_from_into_bytes!(f64, 8, read_f64, write_f64);
+
+macro_rules! impl_odd_width_uint {
+    ($(#[$attr:meta])* $name:ident, $inner:ty, $byte_len:expr) => {
+        $(#[$attr])*
+        ///
+        /// The value is stored in the wrapped integer. Serializing a value which doesn't fit into
+        /// the serialized width fails.
+        #[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $name(pub $inner);
+
+        impl $name {
+            /// The largest value that can be serialized.
+            pub const MAX: $inner = (1 << ($byte_len * 8)) - 1;
+
+            /// Creates the value if it fits into the serialized width.
+            pub fn new(value: $inner) -> Option<Self> {
+                if value <= Self::MAX {
+                    Some($name(value))
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Length for $name {
+            fn to_len(&self) -> Option<usize> {
+                usize::try_from(self.0).ok()
+            }
+
+            fn from_len(len: usize) -> Option<Self> {
+                <$inner>::try_from(len).ok().and_then(Self::new)
+            }
+        }
+
+        impl SerializedByteLen for $name {
+            const BYTE_LEN: usize = $byte_len;
+        }
+
+        impl FromBytesOrdered for $name {
+            fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self {
+                $name(BO::read_uint(bytes, $byte_len) as $inner)
+            }
+        }
+
+        impl IntoBytesOrdered for $name {
+            fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) {
+                assert!(
+                    self.0 <= Self::MAX,
+                    "value {} doesn't fit into {} bytes",
+                    self.0,
+                    $byte_len
+                );
+                BO::write_uint(bytes, self.0.into(), $byte_len)
+            }
+        }
+
+        impl TryFromBytesOrdered for $name {
+            fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error> {
+                check_len::<Self>(bytes)?;
+                Ok($name(BO::read_uint(bytes, $byte_len) as $inner))
+            }
+        }
+
+        impl TryIntoBytesOrdered for $name {
+            fn try_into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+                check_buf_len::<Self>(bytes)?;
+                if self.0 > Self::MAX {
+                    return Err(Error::OutOfRange { field: None });
+                }
+                BO::write_uint(&mut bytes[..$byte_len], self.0.into(), $byte_len);
+                Ok($byte_len)
+            }
+        }
+    };
+}
+
+impl_odd_width_uint!(
+    /// Unsigned integer occupying 3 bytes on wire.
+    U24,
+    u32,
+    3
+);
+impl_odd_width_uint!(
+    /// Unsigned integer occupying 5 bytes on wire.
+    U40,
+    u64,
+    5
+);
+impl_odd_width_uint!(
+    /// Unsigned integer occupying 6 bytes on wire.
+    U48,
+    u64,
+    6
+);
+impl_odd_width_uint!(
+    /// Unsigned integer occupying 7 bytes on wire.
+    U56,
+    u64,
+    7
+);
+
+/// Types which can be stored in bit fields.
+///
+/// Fields marked with `#[bits = N]` are converted to and from the lowest `N` bits of `u128`.
+/// Serializing a value which doesn't fit into `N` bits fails with `Error::OutOfRange`.
+pub trait BitField {
+    /// The maximum number of bits the type can occupy.
+    const BITS: u32;
+
+    /// Converts the value to bits.
+    #[allow(clippy::wrong_self_convention)]
+    fn into_bits(&self) -> u128;
+
+    /// Converts bits to the value.
+    ///
+    /// Only the lowest `N` bits of `bits` may be set, where `N` is the width of the field.
+    fn from_bits(bits: u128) -> Self;
+}
+
+impl BitField for bool {
+    const BITS: u32 = 1;
+
+    fn into_bits(&self) -> u128 {
+        *self as u128
+    }
+
+    fn from_bits(bits: u128) -> Self {
+        bits != 0
+    }
+}
+
+macro_rules! impl_bit_field {
+    ($($type:ty),*) => {
+        $(
+            impl BitField for $type {
+                const BITS: u32 = <$type>::BITS;
+
+                fn into_bits(&self) -> u128 {
+                    *self as u128
+                }
+
+                fn from_bits(bits: u128) -> Self {
+                    bits as $type
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_field!(u8, u16, u32, u64, u128);
+
+/// Types which can hold the length of other field.
+///
+/// Fields marked with `#[struct_deser(len_from = "...")]` take their length from a field of this
+/// type.
+pub trait Length: Sized {
+    /// Converts the value to length, returns `None` if it doesn't fit into `usize`.
+    fn to_len(&self) -> Option<usize>;
+
+    /// Converts length to the value, returns `None` if it doesn't fit into `Self`.
+    fn from_len(len: usize) -> Option<Self>;
+}
+
+macro_rules! impl_length {
+    ($($type:ty),*) => {
+        $(
+            impl Length for $type {
+                fn to_len(&self) -> Option<usize> {
+                    usize::try_from(*self).ok()
+                }
+
+                fn from_len(len: usize) -> Option<Self> {
+                    <$type>::try_from(len).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_length!(u8, u16, u32, u64, u128);
 
 /// This trait can be used for marking specific implementation with a constant, which can be used
 /// for matching, when determinint the type of message.
-/// This doesn't influence derived (de)serialization in any way.
+/// This doesn't influence derived (de)serialization of the type itself, but it's used by derived
+/// (de)serialization of tagged enums containing it.
 pub trait Identifier {
     /// Type of the identifier.
     type IdentifierType;
@@ -163,296 +2376,121 @@ pub trait Identifier {
     const IDENTIFIER: Self::IdentifierType;
 }
 
-macro_rules! impl_byte_arr {
-    ($len:expr) => {
-        impl SerializedByteLen for [u8; $len] {
-            const BYTE_LEN: usize = $len;
-        }
+/// Returns the bytes of `index`-th element of serialized array of `T`.
+fn element<T: SerializedByteLen>(bytes: &[u8], index: usize) -> &[u8] {
+    &bytes[(index * T::BYTE_LEN)..((index + 1) * T::BYTE_LEN)]
+}
+
+/// Returns the bytes of `index`-th element of serialized array of `T`.
+fn element_mut<T: SerializedByteLen>(bytes: &mut [u8], index: usize) -> &mut [u8] {
+    &mut bytes[(index * T::BYTE_LEN)..((index + 1) * T::BYTE_LEN)]
+}
 
-        impl FromBytes for [u8; $len] {
-            fn from_bytes(bytes: &[u8]) -> Self {
-                let mut arr = [0; $len];
-                arr.copy_from_slice(&bytes);
-                arr
+/// Creates an array by calling `f` for each index, stopping at the first error.
+fn try_array_from_fn<T, F, const N: usize>(mut f: F) -> Result<[T; N], Error>
+where
+    F: FnMut(usize) -> Result<T, Error>,
+{
+    let mut error = None;
+    let items: [Option<T>; N] = core::array::from_fn(|index| {
+        if error.is_some() {
+            return None;
+        }
+        match f(index) {
+            Ok(item) => Some(item),
+            Err(err) => {
+                error = Some(err);
+                None
             }
         }
+    });
 
-        impl IntoBytes for [u8; $len] {
-            fn into_bytes(&self, bytes: &mut [u8]) {
-                bytes.copy_from_slice(self)
-            }
+    match error {
+        Some(error) => Err(error),
+        None => Ok(items.map(|item| item.expect("all items were deserialized"))),
+    }
+}
+
+impl<T: SerializedByteLen, const N: usize> SerializedByteLen for [T; N] {
+    const BYTE_LEN: usize = N * T::BYTE_LEN;
+}
+
+// Arrays of other types would need `N * T::BYTE_LEN` in the type, which isn't possible with
+// generic `T`.
+impl<const N: usize> ToByteArray for [u8; N] {
+    type Array = [u8; N];
+
+    fn to_byte_array(&self) -> Self::Array {
+        *self
+    }
+
+    fn from_byte_array(bytes: &Self::Array) -> Self {
+        *bytes
+    }
+}
+
+impl<T: FromBytes, const N: usize> FromBytes for [T; N] {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), Self::BYTE_LEN);
+        core::array::from_fn(|index| T::from_bytes(element::<T>(bytes, index)))
+    }
+}
+
+impl<T: IntoBytes, const N: usize> IntoBytes for [T; N] {
+    fn into_bytes(&self, bytes: &mut [u8]) {
+        assert_eq!(bytes.len(), Self::BYTE_LEN);
+        for (index, item) in self.iter().enumerate() {
+            item.into_bytes(element_mut::<T>(bytes, index));
         }
-    };
+    }
+}
+
+impl<T: FromBytesOrdered, const N: usize> FromBytesOrdered for [T; N] {
+    fn from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), Self::BYTE_LEN);
+        core::array::from_fn(|index| T::from_bytes::<BO>(element::<T>(bytes, index)))
+    }
+}
+
+impl<T: IntoBytesOrdered, const N: usize> IntoBytesOrdered for [T; N] {
+    fn into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) {
+        assert_eq!(bytes.len(), Self::BYTE_LEN);
+        for (index, item) in self.iter().enumerate() {
+            item.into_bytes::<BO>(element_mut::<T>(bytes, index));
+        }
+    }
+}
+
+impl<T: TryFromBytes, const N: usize> TryFromBytes for [T; N] {
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_len::<Self>(bytes)?;
+        try_array_from_fn(|index| T::try_from_bytes(element::<T>(bytes, index)))
+    }
+}
+
+impl<T: TryFromBytesOrdered, const N: usize> TryFromBytesOrdered for [T; N] {
+    fn try_from_bytes<BO: ByteOrder>(bytes: &[u8]) -> Result<Self, Error> {
+        check_len::<Self>(bytes)?;
+        try_array_from_fn(|index| T::try_from_bytes::<BO>(element::<T>(bytes, index)))
+    }
+}
+
+impl<T: TryIntoBytes, const N: usize> TryIntoBytes for [T; N] {
+    fn try_into_bytes(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+        check_buf_len::<Self>(bytes)?;
+        for (index, item) in self.iter().enumerate() {
+            item.try_into_bytes(element_mut::<T>(bytes, index))?;
+        }
+        Ok(Self::BYTE_LEN)
+    }
 }
 
-impl_byte_arr!(0);
-impl_byte_arr!(1);
-impl_byte_arr!(2);
-impl_byte_arr!(3);
-impl_byte_arr!(4);
-impl_byte_arr!(5);
-impl_byte_arr!(6);
-impl_byte_arr!(7);
-impl_byte_arr!(8);
-impl_byte_arr!(9);
-impl_byte_arr!(10);
-impl_byte_arr!(11);
-impl_byte_arr!(12);
-impl_byte_arr!(13);
-impl_byte_arr!(14);
-impl_byte_arr!(15);
-impl_byte_arr!(16);
-impl_byte_arr!(17);
-impl_byte_arr!(18);
-impl_byte_arr!(19);
-impl_byte_arr!(20);
-impl_byte_arr!(21);
-impl_byte_arr!(22);
-impl_byte_arr!(23);
-impl_byte_arr!(24);
-impl_byte_arr!(25);
-impl_byte_arr!(26);
-impl_byte_arr!(27);
-impl_byte_arr!(28);
-impl_byte_arr!(29);
-impl_byte_arr!(30);
-impl_byte_arr!(31);
-impl_byte_arr!(32);
-impl_byte_arr!(33);
-impl_byte_arr!(34);
-impl_byte_arr!(35);
-impl_byte_arr!(36);
-impl_byte_arr!(37);
-impl_byte_arr!(38);
-impl_byte_arr!(39);
-impl_byte_arr!(40);
-impl_byte_arr!(41);
-impl_byte_arr!(42);
-impl_byte_arr!(43);
-impl_byte_arr!(44);
-impl_byte_arr!(45);
-impl_byte_arr!(46);
-impl_byte_arr!(47);
-impl_byte_arr!(48);
-impl_byte_arr!(49);
-impl_byte_arr!(50);
-impl_byte_arr!(51);
-impl_byte_arr!(52);
-impl_byte_arr!(53);
-impl_byte_arr!(54);
-impl_byte_arr!(55);
-impl_byte_arr!(56);
-impl_byte_arr!(57);
-impl_byte_arr!(58);
-impl_byte_arr!(59);
-impl_byte_arr!(60);
-impl_byte_arr!(61);
-impl_byte_arr!(62);
-impl_byte_arr!(63);
-impl_byte_arr!(64);
-impl_byte_arr!(65);
-impl_byte_arr!(66);
-impl_byte_arr!(67);
-impl_byte_arr!(68);
-impl_byte_arr!(69);
-impl_byte_arr!(70);
-impl_byte_arr!(71);
-impl_byte_arr!(72);
-impl_byte_arr!(73);
-impl_byte_arr!(74);
-impl_byte_arr!(75);
-impl_byte_arr!(76);
-impl_byte_arr!(77);
-impl_byte_arr!(78);
-impl_byte_arr!(79);
-impl_byte_arr!(80);
-impl_byte_arr!(81);
-impl_byte_arr!(82);
-impl_byte_arr!(83);
-impl_byte_arr!(84);
-impl_byte_arr!(85);
-impl_byte_arr!(86);
-impl_byte_arr!(87);
-impl_byte_arr!(88);
-impl_byte_arr!(89);
-impl_byte_arr!(90);
-impl_byte_arr!(91);
-impl_byte_arr!(92);
-impl_byte_arr!(93);
-impl_byte_arr!(94);
-impl_byte_arr!(95);
-impl_byte_arr!(96);
-impl_byte_arr!(97);
-impl_byte_arr!(98);
-impl_byte_arr!(99);
-impl_byte_arr!(100);
-impl_byte_arr!(101);
-impl_byte_arr!(102);
-impl_byte_arr!(103);
-impl_byte_arr!(104);
-impl_byte_arr!(105);
-impl_byte_arr!(106);
-impl_byte_arr!(107);
-impl_byte_arr!(108);
-impl_byte_arr!(109);
-impl_byte_arr!(110);
-impl_byte_arr!(111);
-impl_byte_arr!(112);
-impl_byte_arr!(113);
-impl_byte_arr!(114);
-impl_byte_arr!(115);
-impl_byte_arr!(116);
-impl_byte_arr!(117);
-impl_byte_arr!(118);
-impl_byte_arr!(119);
-impl_byte_arr!(120);
-impl_byte_arr!(121);
-impl_byte_arr!(122);
-impl_byte_arr!(123);
-impl_byte_arr!(124);
-impl_byte_arr!(125);
-impl_byte_arr!(126);
-impl_byte_arr!(127);
-impl_byte_arr!(128);
-impl_byte_arr!(129);
-impl_byte_arr!(130);
-impl_byte_arr!(131);
-impl_byte_arr!(132);
-impl_byte_arr!(133);
-impl_byte_arr!(134);
-impl_byte_arr!(135);
-impl_byte_arr!(136);
-impl_byte_arr!(137);
-impl_byte_arr!(138);
-impl_byte_arr!(139);
-impl_byte_arr!(140);
-impl_byte_arr!(141);
-impl_byte_arr!(142);
-impl_byte_arr!(143);
-impl_byte_arr!(144);
-impl_byte_arr!(145);
-impl_byte_arr!(146);
-impl_byte_arr!(147);
-impl_byte_arr!(148);
-impl_byte_arr!(149);
-impl_byte_arr!(150);
-impl_byte_arr!(151);
-impl_byte_arr!(152);
-impl_byte_arr!(153);
-impl_byte_arr!(154);
-impl_byte_arr!(155);
-impl_byte_arr!(156);
-impl_byte_arr!(157);
-impl_byte_arr!(158);
-impl_byte_arr!(159);
-impl_byte_arr!(160);
-impl_byte_arr!(161);
-impl_byte_arr!(162);
-impl_byte_arr!(163);
-impl_byte_arr!(164);
-impl_byte_arr!(165);
-impl_byte_arr!(166);
-impl_byte_arr!(167);
-impl_byte_arr!(168);
-impl_byte_arr!(169);
-impl_byte_arr!(170);
-impl_byte_arr!(171);
-impl_byte_arr!(172);
-impl_byte_arr!(173);
-impl_byte_arr!(174);
-impl_byte_arr!(175);
-impl_byte_arr!(176);
-impl_byte_arr!(177);
-impl_byte_arr!(178);
-impl_byte_arr!(179);
-impl_byte_arr!(180);
-impl_byte_arr!(181);
-impl_byte_arr!(182);
-impl_byte_arr!(183);
-impl_byte_arr!(184);
-impl_byte_arr!(185);
-impl_byte_arr!(186);
-impl_byte_arr!(187);
-impl_byte_arr!(188);
-impl_byte_arr!(189);
-impl_byte_arr!(190);
-impl_byte_arr!(191);
-impl_byte_arr!(192);
-impl_byte_arr!(193);
-impl_byte_arr!(194);
-impl_byte_arr!(195);
-impl_byte_arr!(196);
-impl_byte_arr!(197);
-impl_byte_arr!(198);
-impl_byte_arr!(199);
-impl_byte_arr!(200);
-impl_byte_arr!(201);
-impl_byte_arr!(202);
-impl_byte_arr!(203);
-impl_byte_arr!(204);
-impl_byte_arr!(205);
-impl_byte_arr!(206);
-impl_byte_arr!(207);
-impl_byte_arr!(208);
-impl_byte_arr!(209);
-impl_byte_arr!(210);
-impl_byte_arr!(211);
-impl_byte_arr!(212);
-impl_byte_arr!(213);
-impl_byte_arr!(214);
-impl_byte_arr!(215);
-impl_byte_arr!(216);
-impl_byte_arr!(217);
-impl_byte_arr!(218);
-impl_byte_arr!(219);
-impl_byte_arr!(220);
-impl_byte_arr!(221);
-impl_byte_arr!(222);
-impl_byte_arr!(223);
-impl_byte_arr!(224);
-impl_byte_arr!(225);
-impl_byte_arr!(226);
-impl_byte_arr!(227);
-impl_byte_arr!(228);
-impl_byte_arr!(229);
-impl_byte_arr!(230);
-impl_byte_arr!(231);
-impl_byte_arr!(232);
-impl_byte_arr!(233);
-impl_byte_arr!(234);
-impl_byte_arr!(235);
-impl_byte_arr!(236);
-impl_byte_arr!(237);
-impl_byte_arr!(238);
-impl_byte_arr!(239);
-impl_byte_arr!(240);
-impl_byte_arr!(241);
-impl_byte_arr!(242);
-impl_byte_arr!(243);
-impl_byte_arr!(244);
-impl_byte_arr!(245);
-impl_byte_arr!(246);
-impl_byte_arr!(247);
-impl_byte_arr!(248);
-impl_byte_arr!(249);
-impl_byte_arr!(250);
-impl_byte_arr!(251);
-impl_byte_arr!(252);
-impl_byte_arr!(253);
-impl_byte_arr!(254);
-impl_byte_arr!(255);
-impl_byte_arr!(256);
-impl_byte_arr!(512);
-impl_byte_arr!(1024);
-impl_byte_arr!(2048);
-impl_byte_arr!(4096);
-impl_byte_arr!(8192);
-impl_byte_arr!(16384);
-impl_byte_arr!(32768);
-impl_byte_arr!(65536);
-impl_byte_arr!(131072);
-impl_byte_arr!(262144);
-impl_byte_arr!(524288);
-impl_byte_arr!(1048576);
-impl_byte_arr!(2097152);
-impl_byte_arr!(4194304);
+impl<T: TryIntoBytesOrdered, const N: usize> TryIntoBytesOrdered for [T; N] {
+    fn try_into_bytes<BO: ByteOrder>(&self, bytes: &mut [u8]) -> Result<usize, Error> {
+        check_buf_len::<Self>(bytes)?;
+        for (index, item) in self.iter().enumerate() {
+            item.try_into_bytes::<BO>(element_mut::<T>(bytes, index))?;
+        }
+        Ok(Self::BYTE_LEN)
+    }
+}
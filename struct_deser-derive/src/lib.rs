@@ -1,218 +1,2951 @@
 //! Derive proc macro for `struct_deser` crate. See that one for more information.
 
 extern crate proc_macro;
-extern crate syn;
+extern crate proc_macro2;
 #[macro_use]
 extern crate quote;
+extern crate syn;
 
 use proc_macro::TokenStream;
-use quote::{ToTokens, Tokens};
+use proc_macro2::{Span, TokenStream as Tokens};
+use quote::{ToTokens, TokenStreamExt};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 
-#[proc_macro_derive(StructDeser, attributes(struct_deser, be, le))]
+#[proc_macro_derive(StructDeser, attributes(struct_deser, be, le, ne, bits))]
 pub fn derive_struct_deser(input: TokenStream) -> TokenStream {
-    let s = input.to_string();
-    let ast = syn::parse_derive_input(&s).unwrap();
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    let gen = match ast.data {
+        syn::Data::Struct(ref data) => impl_struct_deser(&ast, data),
+        syn::Data::Enum(ref data) => impl_enum(&ast, data),
+        syn::Data::Union(ref data) => Err(syn::Error::new_spanned(
+            data.union_token,
+            "The type must be a struct or an enum",
+        )),
+    };
 
-    let gen = impl_struct_deser(&ast);
+    gen.unwrap_or_else(compile_errors).into()
+}
 
-    gen.parse().unwrap()
+// Converts the error into `compile_error!` invocations pointing at the offending tokens
+//
+// `syn::Error::into_compile_error` can't be used because it refers to `::core`, which doesn't
+// resolve in crates without `extern crate core`.
+fn compile_errors(error: syn::Error) -> Tokens {
+    error
+        .into_iter()
+        .map(|error| {
+            let message = error.to_string();
+            quote_spanned! { error.span() =>
+                compile_error!(#message);
+            }
+        })
+        .collect()
 }
 
 // Not to be confused  with one in byteorder crate...
-#[derive(Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq)]
 enum ByteOrder {
     LE,
     BE,
+    NE,
 }
 
 impl ToTokens for ByteOrder {
     fn to_tokens(&self, tokens: &mut Tokens) {
-        match *self {
-            ByteOrder::LE => tokens.append("LE"),
-            ByteOrder::BE => tokens.append("BE"),
-        }
+        let name = match *self {
+            ByteOrder::LE => "LE",
+            ByteOrder::BE => "BE",
+            ByteOrder::NE => "NE",
+        };
+        tokens.append(syn::Ident::new(name, Span::call_site()));
+    }
+}
+
+// Order of bits in bit fields
+#[derive(Copy, Clone, Eq, PartialEq, Default)]
+enum BitOrder {
+    // The first field occupies the most significant bits of big endian integer
+    #[default]
+    Msb,
+    // The first field occupies the least significant bits of little endian integer
+    Lsb,
+}
+
+// Byte order used for (de)serializing a field
+#[derive(Copy, Clone)]
+enum FieldOrder {
+    // The type of the field doesn't depend on byte order
+    None,
+    // The byte order was specified using attributes
+    Fixed(ByteOrder),
+    // The byte order is chosen by the caller of ordered traits
+    Inherited,
+}
+
+// Which family of traits is being implemented
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Mode {
+    // `FromBytes`, `IntoBytes`, ...
+    Plain,
+    // `FromBytesOrdered`, `IntoBytesOrdered`, ...
+    Ordered,
+}
+
+// Dispatches enums based on presence of discriminant representation
+fn impl_enum(ast: &syn::DeriveInput, data: &syn::DataEnum) -> syn::Result<Tokens> {
+    let attrs = TypeAttrs::parse(&ast.attrs)?;
+    let variants = data.variants.iter().collect::<Vec<_>>();
+
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "(De)serializing empty enum doesn't make sense",
+        ));
+    }
+
+    match get_repr(&ast.attrs, &attrs)? {
+        Some(repr) => impl_enum_deser(ast, &attrs, &variants, &repr),
+        None => impl_tagged_enum_deser(ast, &attrs, &variants),
     }
 }
 
 // Actual implementation
-fn impl_struct_deser(ast: &syn::DeriveInput) -> quote::Tokens {
-    use quote::Ident;
-    use syn::{Body, VariantData};
+fn impl_struct_deser(ast: &syn::DeriveInput, data: &syn::DataStruct) -> syn::Result<Tokens> {
+    use syn::Fields;
 
-    let mut res = quote::Tokens::new();
-    let body = if let Body::Struct(ref body) = ast.body {
-        body
-    } else {
-        panic!("The type must be a struct");
-    };
+    if let Fields::Unit = data.fields {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "(De)serializing empty struct doesn't make sense",
+        ));
+    }
+
+    let attrs = TypeAttrs::parse(&ast.attrs)?;
+    let field_attrs = data
+        .fields
+        .iter()
+        .map(|field| FieldAttrs::parse(&field.attrs))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let all_fields = data
+        .fields
+        .iter()
+        .zip(&field_attrs)
+        .enumerate()
+        .map(|(index, (field, attrs))| StructField {
+            index,
+            field,
+            attrs,
+        })
+        .collect::<Vec<_>>();
+
+    // Warnings are emitted even if the struct is rejected, so the error doesn't hide them
+    let mut warnings = Tokens::new();
+    if !attrs.ordered && attrs.byte_order.is_none() {
+        for field in &all_fields {
+            if field.attrs.byte_order.is_none() && field.attrs.bits.is_none() {
+                let note = format!(
+                    "Field `{}` of `{}` has no byte order, specify it using `#[be]`, `#[le]`, `#[ne]` or `#[struct_deser(byte_order = \"...\")]`",
+                    field.name(),
+                    ast.ident
+                );
+                warn_missing_byte_order(&field.field.ty, &note, field.index, &mut warnings);
+            }
+        }
+    }
+
+    impl_struct_traits(ast, data, &attrs, &all_fields, warnings.clone()).or_else(|error| {
+        let mut res = wrap_impls(warnings);
+        res.extend(compile_errors(error));
+        Ok(res)
+    })
+}
+
+// Impls traits of the struct, `res` holds the tokens generated so far
+fn impl_struct_traits(
+    ast: &syn::DeriveInput,
+    data: &syn::DataStruct,
+    attrs: &TypeAttrs,
+    all_fields: &[StructField],
+    mut res: Tokens,
+) -> syn::Result<Tokens> {
+    use syn::Fields;
 
-    impl_identifier(ast, &mut res);
+    impl_identifier(ast, attrs, &mut res);
 
     let name = &ast.ident;
-    let dummy_const = Ident::new(format!("_IMPL_STRUCT_DESER_FOR_{}", name));
+    let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
+    let ordered = attrs.ordered;
+    let payload = get_payload(all_fields)?;
+    let fields = match payload {
+        Some(_) => &all_fields[..all_fields.len() - 1],
+        None => &all_fields[..],
+    };
+    let mut segments = get_segments(fields, attrs.magic.clone())?;
+
+    if let Some(pad) = get_pad(&attrs.pad, None, None) {
+        if let Some(ref payload) = payload {
+            return Err(payload.field.error("Padding can't follow payload"));
+        }
+
+        match segments.last_mut() {
+            Some(Segment::Fixed(ref mut layout)) => layout.push(pad),
+            _ => segments.push(Segment::Fixed(vec![pad])),
+        }
+    }
+
+    for segment in &segments {
+        if let Segment::Fixed(ref layout) = *segment {
+            check_layout(ast, layout, &mut res);
+        }
+    }
+
+    // The length of structs ending with payload or containing fields with length given by other
+    // fields isn't known until they are parsed, so only the length of the fixed part is provided.
+    let variable = segments
+        .iter()
+        .any(|segment| matches!(*segment, Segment::Counted(_)));
+    if payload.is_some() || variable {
+        if ordered {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Structs with variable length can't be ordered",
+            ));
+        }
+
+        if attrs.view {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Views of structs with variable length aren't supported",
+            ));
+        }
+
+        if let Some((_, span)) = attrs.size {
+            return Err(syn::Error::new(
+                span,
+                "Size can't be specified for structs with variable length",
+            ));
+        }
+
+        let items = segments.iter().flat_map(Segment::items);
+        let mut byte_len = quote! { 0 };
+        for item in items.clone() {
+            let item_len = item.byte_len();
+            byte_len.extend(quote! { + #item_len });
+        }
+
+        let byte_len_bounds = add_bounds(&ast.generics, items, |_| {
+            quote! { _struct_deser::SerializedByteLen }
+        });
+
+        res.extend(quote! {
+            impl #impl_generics #name #ty_generics #byte_len_bounds {
+                /// The number of bytes occupied by the fields of fixed length.
+                pub const HEADER_LEN: usize = #byte_len;
+            }
+        });
+
+        impl_variable_traits(
+            ast,
+            &data.fields,
+            &segments,
+            payload.as_ref(),
+            attrs,
+            &mut res,
+        );
+        return Ok(wrap_impls(res));
+    }
+
+    let layout = match segments.into_iter().next() {
+        Some(Segment::Fixed(layout)) => layout,
+        _ => Vec::new(),
+    };
 
-    let mut deser_body = quote::Tokens::new();
-    let mut ser_body = quote::Tokens::new();
     let mut byte_len = quote! { 0 };
-    for (field_no, field) in body.fields().iter().enumerate() {
-        let ty = &field.ty;
+    for item in &layout {
+        let item_len = item.byte_len();
+        byte_len.extend(quote! { + #item_len });
+    }
+
+    let byte_len_bounds = add_bounds(&ast.generics, &layout, |_| {
+        quote! { _struct_deser::SerializedByteLen }
+    });
+
+    res.extend(quote! {
+        impl #impl_generics _struct_deser::SerializedByteLen for #name #ty_generics #byte_len_bounds {
+            const BYTE_LEN: usize = #byte_len;
+        }
+    });
+
+    // Consts can't use generic parameters
+    if let Some((size, span)) = attrs.size.filter(|_| ast.generics.params.is_empty()) {
+        let message = format!("Size of `{}` doesn't match the length of its fields", name);
+        res.extend(quote_spanned! { span =>
+            const _: () = assert!(<#name as _struct_deser::SerializedByteLen>::BYTE_LEN == #size, #message);
+        });
+    }
+
+    impl_field_info(ast, &layout, attrs, &mut res);
+
+    // Ordered structs without default byte order have no way to determine the byte order of
+    // unannotated fields when using plain traits.
+    if !ordered || attrs.byte_order.is_some() {
+        impl_traits(ast, &data.fields, &layout, Mode::Plain, attrs, &mut res);
+    }
+
+    if ordered {
+        impl_traits(ast, &data.fields, &layout, Mode::Ordered, attrs, &mut res);
+    }
+    res.extend(impl_ordered_helpers(ast, ordered));
+
+    if attrs.view {
+        if !ast.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &ast.generics,
+                "Views of generic structs aren't supported",
+            ));
+        }
+
+        if ordered && attrs.byte_order.is_none() {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Views of ordered structs require default byte order",
+            ));
+        }
+
+        if let Fields::Unnamed(_) = data.fields {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Views of tuple structs aren't supported",
+            ));
+        }
 
-        let byte_order = get_byte_order(&field.attrs);
+        let (defs, impls) = impl_view(ast, &layout, attrs);
+        res.extend(impls);
+        let impls = wrap_impls(res);
+        return Ok(quote! {
+            #defs
+            #impls
+        });
+    }
+
+    Ok(wrap_impls(res))
+}
+
+// Field of a struct along with its position and options
+#[derive(Copy, Clone)]
+struct StructField<'a> {
+    index: usize,
+    field: &'a syn::Field,
+    attrs: &'a FieldAttrs,
+}
+
+impl<'a> StructField<'a> {
+    // Name of the field used in errors
+    fn name(&self) -> String {
+        match self.field.ident {
+            // Raw identifiers are named without `r#`, so that the name can be used in other
+            // identifiers
+            Some(ref ident) => ident.unraw().to_string(),
+            None => self.index.to_string(),
+        }
+    }
 
-        let field_accessor = match field.ident {
+    // Tokens accessing the field on a struct
+    fn accessor(&self) -> Tokens {
+        match self.field.ident {
             Some(ref ident) => quote! { #ident },
+            // Interpolating the index directly would cause adding `usize` sufix
+            None => syn::Index::from(self.index).into_token_stream(),
+        }
+    }
+
+    // Local variable holding deserialized value of the field
+    fn var(&self) -> syn::Ident {
+        format_ident!("__field_{}", self.index)
+    }
+
+    // Location of the name of the field or its type in tuple structs
+    fn span(&self) -> Span {
+        match self.field.ident {
+            Some(ref ident) => ident.span(),
+            None => self.field.ty.span(),
+        }
+    }
+
+    // Creates error pointing at the field
+    fn error<T: std::fmt::Display>(&self, message: T) -> syn::Error {
+        syn::Error::new(self.span(), message)
+    }
+}
+
+// Trailing field borrowing the rest of the input
+struct Payload<'a> {
+    field: StructField<'a>,
+    lifetime: syn::Lifetime,
+}
+
+// Returns the last field if its type is `&'a [u8]`
+fn get_payload<'a>(fields: &[StructField<'a>]) -> syn::Result<Option<Payload<'a>>> {
+    let field = match fields.last() {
+        Some(field) => *field,
+        None => return Ok(None),
+    };
+    if field.attrs.len_from.is_some() {
+        return Ok(None);
+    }
+
+    Ok(borrowed_bytes(&field.field.ty)?.map(|lifetime| Payload { field, lifetime }))
+}
+
+// Returns the lifetime of the type if it's `&'a [u8]`
+fn borrowed_bytes(ty: &syn::Type) -> syn::Result<Option<syn::Lifetime>> {
+    use syn::Type;
+
+    match *ty {
+        Type::Reference(ref reference) if reference.mutability.is_none() => match *reference.elem {
+            Type::Slice(ref slice) if quote!(#slice).to_string() == "[u8]" => {
+                match reference.lifetime {
+                    Some(ref lifetime) => Ok(Some(lifetime.clone())),
+                    None => Err(syn::Error::new_spanned(
+                        ty,
+                        "Borrowed bytes must have a lifetime",
+                    )),
+                }
+            }
+            _ => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+// Returns the type of elements if the type is `Vec<T>`
+fn vec_elem(ty: &syn::Type) -> Option<&syn::Type> {
+    use syn::{GenericArgument, PathArguments, Type};
+
+    if let Type::Path(ref path) = *ty {
+        if path.qself.is_some() {
+            return None;
+        }
+
+        let segment = path.path.segments.last()?;
+        if let PathArguments::AngleBracketed(ref params) = segment.arguments {
+            if segment.ident == "Vec" && params.args.len() == 1 {
+                if let GenericArgument::Type(ref elem) = params.args[0] {
+                    return Some(elem);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// Part of struct separated by fields of variable length
+enum Segment<'a> {
+    // Consecutive items of fixed length
+    Fixed(Vec<Item<'a>>),
+    // Field of variable length
+    Counted(Counted<'a>),
+}
+
+impl<'a> Segment<'a> {
+    // Items of fixed length in the segment
+    fn items(&self) -> &[Item<'a>] {
+        match *self {
+            Segment::Fixed(ref layout) => layout,
+            Segment::Counted(_) => &[],
+        }
+    }
+}
+
+// Field with the number of bytes or elements stored in other field
+struct Counted<'a> {
+    field: StructField<'a>,
+    len: StructField<'a>,
+    elem: Elem<'a>,
+}
+
+// Contents of a field with variable length
+enum Elem<'a> {
+    // `&'a [u8]`
+    Borrowed(syn::Lifetime),
+    // `Vec<u8>`
+    Bytes,
+    // `Vec<T>` where `T` has fixed length
+    Items(&'a syn::Type),
+}
+
+impl<'a> Elem<'a> {
+    // Expression evaluating to the number of bytes occupied by single element
+    fn byte_len(&self) -> Tokens {
+        match *self {
+            Elem::Borrowed(_) | Elem::Bytes => quote! { 1 },
+            Elem::Items(ty) => quote! { <#ty as _struct_deser::SerializedByteLen>::BYTE_LEN },
+        }
+    }
+}
+
+// Splits fields into segments of fixed length and fields of variable length
+//
+// `magic` is placed before the first field.
+fn get_segments<'a>(
+    fields: &[StructField<'a>],
+    magic: Option<Vec<u8>>,
+) -> syn::Result<Vec<Segment<'a>>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut len_fields = Vec::new();
+    let mut layout = magic.map(Item::Magic).into_iter().collect::<Vec<_>>();
+    // Offsets of fields are unknown after the first field of variable length
+    let mut offset = Some(quote! { 0 });
+
+    for (index, &field) in fields.iter().enumerate() {
+        let len_name = match field.attrs.len_from {
+            Some(ref len_name) => len_name,
+            None => continue,
+        };
+
+        let len = match fields[..index]
+            .iter()
+            .find(|len| len.name() == len_name.value())
+        {
+            Some(len) => *len,
             None => {
-                // Interpolating directly would cause adding `usize` sufix
-                let mut tmp = Tokens::new();
-                tmp.append(format!("{}", field_no));
-                tmp
+                return Err(syn::Error::new_spanned(
+                    len_name,
+                    format!(
+                        "Length of `{}` must be stored in a preceding field, but there's no field `{}` before it",
+                        field.name(),
+                        len_name.value()
+                    ),
+                ))
             }
         };
 
-        let byte_slice = quote! { bytes[(#byte_len)..(#byte_len + <#ty as _struct_deser::SerializedByteLen>::BYTE_LEN)] };
-
-        let (deser_impl, ser_impl) = match byte_order {
-            None => (
-                quote! { _struct_deser::FromBytes::from_bytes(&#byte_slice) },
-                quote! { _struct_deser::IntoBytes::into_bytes(&self.#field_accessor, &mut #byte_slice); },
-            ),
-            Some(bo) => (
-                quote! { _struct_deser::FromBytesOrdered::from_bytes::<_struct_deser::byteorder::#bo>(&#byte_slice) },
-                quote! { _struct_deser::IntoBytesOrdered::into_bytes::<_struct_deser::byteorder::#bo>(&self.#field_accessor, &mut #byte_slice); },
-            ),
+        if len.attrs.bits.is_some() {
+            return Err(syn::Error::new_spanned(
+                len_name,
+                format!("Length of `{}` can't be stored in bit field", field.name()),
+            ));
+        }
+
+        if len_fields.contains(&len.index) {
+            return Err(syn::Error::new_spanned(
+                len_name,
+                format!("Field `{}` stores length of multiple fields", len.name()),
+            ));
+        }
+        len_fields.push(len.index);
+
+        let elem = match (borrowed_bytes(&field.field.ty)?, vec_elem(&field.field.ty)) {
+            (Some(lifetime), _) => Elem::Borrowed(lifetime),
+            (None, Some(elem)) if quote!(#elem).to_string() == "u8" => Elem::Bytes,
+            (None, Some(elem)) => Elem::Items(elem),
+            (None, None) => {
+                return Err(syn::Error::new_spanned(
+                    &field.field.ty,
+                    format!(
+                        "Field `{}` with length given by other field must be either `&[u8]` or `Vec<T>`",
+                        field.name()
+                    ),
+                ))
+            }
         };
 
-        deser_body.append(match field.ident {
-            Some(ref ident) => quote! { #ident: #deser_impl, },
-            None => quote! { #deser_impl, },
+        let fixed = get_layout(&fields[start..index], &layout, offset.as_ref())?;
+        layout.extend(fixed);
+        if field.attrs.offset.is_some() && offset.is_none() {
+            return Err(field.error(format!(
+                "Offset of field `{}` can't be specified after field of variable length",
+                field.name()
+            )));
+        }
+        let field_offset = offset.map(|offset| {
+            let layout_len = layout_len(&layout);
+            quote! { #offset + #layout_len }
         });
-        ser_body.append(ser_impl);
+        layout.extend(get_pad(
+            &field.attrs.pad,
+            field.attrs.offset,
+            field_offset.as_ref(),
+        ));
+        if !layout.is_empty() {
+            segments.push(Segment::Fixed(layout));
+        }
+        segments.push(Segment::Counted(Counted { field, len, elem }));
+        layout = Vec::new();
+        offset = None;
+        start = index + 1;
+    }
 
-        byte_len.append(quote! { + <#ty as _struct_deser::SerializedByteLen>::BYTE_LEN });
+    if start < fields.len() || segments.is_empty() || !layout.is_empty() {
+        let fixed = get_layout(&fields[start..], &layout, offset.as_ref())?;
+        layout.extend(fixed);
+        segments.push(Segment::Fixed(layout));
     }
 
-    match *body {
-        VariantData::Struct(_) => res.append(quote! {
-            impl _struct_deser::FromBytes for #name {
-                fn from_bytes(bytes: &[u8]) -> Self {
-                    assert_eq!(bytes.len(), <Self as _struct_deser::SerializedByteLen>::BYTE_LEN);
+    Ok(segments)
+}
 
-                    #name {
-                        #deser_body
-                    }
-                }
-            }
-        }),
-        VariantData::Tuple(_) => res.append(quote! {
-            impl _struct_deser::FromBytes for #name {
-                fn from_bytes(bytes: &[u8]) -> Self {
-                    assert_eq!(bytes.len(), <Self as _struct_deser::SerializedByteLen>::BYTE_LEN);
+// Part of serialized struct occupying a continuous range of bytes
+enum Item<'a> {
+    // Field (de)serialized using its own impls
+    Field(StructField<'a>),
+    // Consecutive bit fields with their widths packed into `byte_len` bytes
+    Bits {
+        fields: Vec<(StructField<'a>, u32)>,
+        byte_len: usize,
+    },
+    // Magic bytes which don't appear in the struct
+    Magic(Vec<u8>),
+    // Padding filled with `fill` bytes, which are checked during deserialization if `verify` is
+    // set
+    Pad {
+        len: Tokens,
+        fill: u8,
+        verify: bool,
+    },
+}
 
-                    #name(#deser_body)
-                }
+impl<'a> Item<'a> {
+    // Expression evaluating to the number of bytes occupied by the item
+    fn byte_len(&self) -> Tokens {
+        match *self {
+            Item::Field(field) => {
+                let ty = &field.field.ty;
+                quote! { <#ty as _struct_deser::SerializedByteLen>::BYTE_LEN }
             }
-        }),
-        VariantData::Unit => panic!("(De)serializing empty struct doesn't make sense"),
+            Item::Bits { byte_len, .. } => quote! { #byte_len },
+            Item::Magic(ref magic) => {
+                let byte_len = magic.len();
+                quote! { #byte_len }
+            }
+            Item::Pad { ref len, .. } => len.clone(),
+        }
     }
 
-    res.append(quote! {
-        impl _struct_deser::IntoBytes for #name {
-            fn into_bytes(&self, bytes: &mut [u8]) {
-                assert_eq!(bytes.len(), <Self as _struct_deser::SerializedByteLen>::BYTE_LEN);
-                #ser_body
+    // The first field in the item
+    fn first_field(&self) -> Option<StructField<'a>> {
+        match *self {
+            Item::Field(field) => Some(field),
+            Item::Bits { ref fields, .. } => Some(fields[0].0),
+            Item::Magic(_) | Item::Pad { .. } => None,
+        }
+    }
+
+    // All fields in the item
+    fn fields(&self) -> Vec<StructField<'a>> {
+        match *self {
+            Item::Field(field) => vec![field],
+            Item::Bits { ref fields, .. } => fields.iter().map(|&(field, _)| field).collect(),
+            Item::Magic(_) | Item::Pad { .. } => Vec::new(),
+        }
+    }
+}
+
+// Groups fields into items; consecutive bit fields are packed together
+//
+// `preceding` are the items before the fields and `start` is the offset of the first of them if
+// it's known.
+fn get_layout<'a>(
+    fields: &[StructField<'a>],
+    preceding: &[Item],
+    start: Option<&Tokens>,
+) -> syn::Result<Vec<Item<'a>>> {
+    let mut layout = Vec::new();
+    let mut bit_fields = Vec::new();
+    let mut bit_count = 0;
+    let mut last_offset = None;
+
+    for &field in fields {
+        if let Some(offset) = field.attrs.offset {
+            if start.is_none() {
+                return Err(field.error(format!(
+                    "Offset of field `{}` can't be specified after field of variable length",
+                    field.name()
+                )));
             }
+
+            if last_offset.is_some_and(|last_offset| offset < last_offset) {
+                return Err(field.error(format!(
+                    "Offset of field `{}` is lower than offset of preceding field",
+                    field.name()
+                )));
+            }
+            last_offset = Some(offset);
         }
 
-        impl _struct_deser::SerializedByteLen for #name {
-            const BYTE_LEN: usize = #byte_len;
+        if field.attrs.offset.is_some() || field.attrs.pad.len.is_some() {
+            if !bit_fields.is_empty() {
+                layout.push(bit_group(bit_fields, bit_count)?);
+                bit_fields = Vec::new();
+                bit_count = 0;
+            }
+
+            let field_offset = start.map(|start| {
+                let preceding_len = layout_len(preceding);
+                let layout_len = layout_len(&layout);
+                quote! { #start + #preceding_len + #layout_len }
+            });
+            layout.extend(get_pad(
+                &field.attrs.pad,
+                field.attrs.offset,
+                field_offset.as_ref(),
+            ));
         }
-    });
 
-    res = quote! {
-        #[allow(non_upper_case_globals)]
-        const #dummy_const: () = {
-            extern crate struct_deser as _struct_deser;
-            #res
-        };
-    };
+        match field.attrs.bits {
+            Some(width) => {
+                bit_count += width;
+                bit_fields.push((field, width));
+            }
+            None => {
+                if !bit_fields.is_empty() {
+                    layout.push(bit_group(bit_fields, bit_count)?);
+                    bit_fields = Vec::new();
+                    bit_count = 0;
+                }
 
-    /*
-    if name == "IntegersTuple" {
-        panic!(res.to_string());
+                layout.push(Item::Field(field));
+            }
+        }
     }
-    */
-    res
+
+    if !bit_fields.is_empty() {
+        layout.push(bit_group(bit_fields, bit_count)?);
+    }
+
+    Ok(layout)
 }
 
-// Impls identifier trait
-fn impl_identifier(ast: &syn::DeriveInput, res: &mut Tokens) {
-    use syn::{Lit, MetaItem, NestedMetaItem};
+// Expression evaluating to the number of bytes occupied by the items
+fn layout_len(layout: &[Item]) -> Tokens {
+    let mut byte_len = quote! { 0 };
+    for item in layout {
+        let item_len = item.byte_len();
+        byte_len.extend(quote! { + #item_len });
+    }
 
-    let name = &ast.ident;
+    byte_len
+}
 
-    for attr in &ast.attrs {
-        if attr.value.name() == "struct_deser" {
-            if let MetaItem::List(_, ref nested) = attr.value {
-                let mut val = None;
-                let mut ty = None;
-                for item in nested {
-                    if let NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref value)) =
-                        *item
-                    {
-                        if name == "identifier" {
-                            val = Some(value);
-                        }
+// Creates item from consecutive bit fields, checking that they occupy whole bytes
+//
+// `is_multiple_of` would require Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+fn bit_group(fields: Vec<(StructField<'_>, u32)>, bit_count: u32) -> syn::Result<Item<'_>> {
+    let names = fields
+        .iter()
+        .map(|&(field, _)| format!("`{}`", field.name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let last = fields[fields.len() - 1].0;
 
-                        if name == "identifier_type" {
-                            ty = Some(value);
-                        }
-                    }
+    if bit_count % 8 != 0 {
+        return Err(last.error(format!(
+            "Bit fields {} occupy {} bits, which doesn't end on byte boundary",
+            names, bit_count
+        )));
+    }
+
+    if bit_count > 128 {
+        return Err(last.error(format!(
+            "Bit fields {} occupy {} bits, but at most 128 consecutive bits are supported",
+            names, bit_count
+        )));
+    }
+
+    Ok(Item::Bits {
+        fields,
+        byte_len: bit_count as usize / 8,
+    })
+}
+
+// Checks that bit fields fit into their types, magic bytes have the length of their fields and
+// fields with explicit offsets don't overlap preceding fields
+fn check_layout(ast: &syn::DeriveInput, layout: &[Item], res: &mut Tokens) {
+    for (item_no, item) in layout.iter().enumerate() {
+        let offset = item.first_field().and_then(|field| field.attrs.offset);
+        if let (Some(offset), Some(field)) = (offset, item.first_field()) {
+            // The field is preceded by padding filling the gap
+            let preceding = &layout[..(item_no - 1)];
+            let generic = preceding.iter().any(|item| {
+                item.fields()
+                    .iter()
+                    .any(|field| depends_on_generics(&field.field.ty, &ast.generics))
+            });
+
+            if !generic {
+                let preceding_len = layout_len(preceding);
+                let message = format!(
+                    "Field `{}` at offset {} overlaps preceding fields",
+                    field.name(),
+                    offset
+                );
+                res.extend(quote_spanned! { field.span() =>
+                    const _: () = assert!(#preceding_len <= #offset, #message);
+                });
+            }
+        }
+
+        if let Item::Field(field) = *item {
+            let ty = &field.field.ty;
+            if let Some(Constant::Magic(ref magic)) = field.attrs.constant {
+                if depends_on_generics(ty, &ast.generics) {
+                    continue;
                 }
 
-                match (val, ty) {
-                    (Some(&Lit::Str(ref val, _)), Some(&Lit::Str(ref ty, _))) => {
-                        let ty = syn::parse_type(ty).expect("expected type");
-                        let val = syn::parse_expr(val).expect("expected expression");
+                let magic_len = magic.len();
+                let message = format!(
+                    "Magic of field `{}` doesn't have the length of its type",
+                    field.name()
+                );
+                res.extend(quote_spanned! { field.span() =>
+                    const _: () = assert!(#magic_len == <#ty as _struct_deser::SerializedByteLen>::BYTE_LEN, #message);
+                });
+            }
+        }
 
-                        res.append(quote! {
-                            impl _struct_deser::Identifier for #name {
-                                type IdentifierType = #ty;
-                                const IDENTIFIER: Self::IdentifierType = #val;
-                            }
-                        });
-                        return;
-                    }
-                    (None, None) => (),
-                    (Some(_), Some(_)) => panic!("Identifier and it's type must be inside string"),
-                    _ => panic!("Both identifier and type must be specified or none of them"),
+        if let Item::Bits { ref fields, .. } = *item {
+            for &(field, width) in fields {
+                let ty = &field.field.ty;
+                // Consts can't use generic parameters
+                if depends_on_generics(ty, &ast.generics) {
+                    continue;
                 }
+
+                let message = format!("Bit field `{}` is wider than its type", field.name());
+                res.extend(quote_spanned! { field.span() =>
+                    const _: () = assert!(#width <= <#ty as _struct_deser::BitField>::BITS, #message);
+                });
             }
         }
     }
 }
 
-// Scans attributes for byte order
-fn get_byte_order(attrs: &[syn::Attribute]) -> Option<ByteOrder> {
-    use syn::MetaItem;
+// Warns if the type holds numbers longer than one byte, which can't be (de)serialized without
+// byte order
+//
+// The errors about unsatisfied trait bounds don't explain what's missing. Proc macros can't emit
+// warnings, so use of deprecated const pointing at the type is generated instead.
+fn warn_missing_byte_order(ty: &syn::Type, note: &str, id: usize, res: &mut Tokens) {
+    let is_number = |ty: &syn::Type| {
+        let numbers = [
+            "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128", "f32", "f64", "U24", "U40",
+            "U48", "U56",
+        ];
+        let ty = quote!(#ty).to_string();
+        numbers.contains(&ty.as_str())
+    };
+    let has_number = match *ty {
+        syn::Type::Array(ref array) => is_number(&array.elem),
+        _ => is_number(ty) || vec_elem(ty).is_some_and(is_number),
+    };
+    if !has_number {
+        return;
+    }
+
+    // The lint isn't reported in derive expansion, so the const must have the span of the type
+    let warning = format_ident!("__missing_byte_order_{}", id, span = ty.span());
+    res.extend(quote_spanned! { ty.span() =>
+        #[deprecated(note = #note)]
+        #[allow(non_upper_case_globals)]
+        const #warning: () = ();
+        const _: () = #warning;
+    });
+}
 
-    let mut byte_order = None;
-    for attr in attrs {
-        if let MetaItem::Word(ref word) = attr.value {
-            if word.as_ref() == "be" {
-                if byte_order == Some(ByteOrder::LE) {
-                    panic!("Conflicting byte order: you can't specify both Little and Big endian");
+// Impls inherent consts describing the layout of fields
+fn impl_field_info(ast: &syn::DeriveInput, layout: &[Item], attrs: &TypeAttrs, res: &mut Tokens) {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
+    let bounds = add_bounds(&ast.generics, layout, |_| {
+        quote! { _struct_deser::SerializedByteLen }
+    });
+    let mode = if attrs.ordered {
+        Mode::Ordered
+    } else {
+        Mode::Plain
+    };
+    let msb_first = attrs.bit_order == BitOrder::Msb;
+
+    let mut infos = Tokens::new();
+    let mut offsets = Tokens::new();
+    let mut offset = quote! { 0 };
+    for item in layout {
+        let item_len = item.byte_len();
+        let fields = match *item {
+            Item::Field(field) => {
+                let byte_order = match get_field_order(field, mode, attrs.byte_order) {
+                    FieldOrder::None => quote! { None },
+                    FieldOrder::Fixed(ByteOrder::BE) => quote! { Big },
+                    FieldOrder::Fixed(ByteOrder::LE) => quote! { Little },
+                    FieldOrder::Fixed(ByteOrder::NE) => quote! { Native },
+                    FieldOrder::Inherited => quote! { Inherited },
+                };
+                vec![(field, byte_order, quote! { _core::option::Option::None })]
+            }
+            Item::Bits {
+                ref fields,
+                byte_len: group_len,
+            } => {
+                let byte_order = if msb_first {
+                    quote! { Big }
+                } else {
+                    quote! { Little }
+                };
+                bit_positions(fields, group_len, msb_first)
+                    .into_iter()
+                    .zip(fields)
+                    .map(|((field, shift, _), &(_, width))| {
+                        let bits = quote! {
+                            _core::option::Option::Some(_struct_deser::BitRange {
+                                shift: #shift,
+                                width: #width,
+                            })
+                        };
+                        (field, byte_order.clone(), bits)
+                    })
+                    .collect()
+            }
+            Item::Magic(_) | Item::Pad { .. } => Vec::new(),
+        };
+
+        for (field, byte_order, bits) in fields {
+            let field_name = field.name();
+            let ty = &field.field.ty;
+            let offset_name = format_ident!("OFFSET_{}", field_name.to_uppercase());
+            let offset_doc = format!("Offset of field `{}` in serialized bytes.", field_name);
+
+            infos.extend(quote! {
+                _struct_deser::FieldInfo {
+                    name: #field_name,
+                    offset: #offset,
+                    byte_len: #item_len,
+                    byte_order: _struct_deser::FieldByteOrder::#byte_order,
+                    type_name: stringify!(#ty),
+                    bits: #bits,
+                },
+            });
+            offsets.extend(quote! {
+                #[doc = #offset_doc]
+                pub const #offset_name: usize = #offset;
+            });
+        }
+
+        offset.extend(quote! { + #item_len });
+    }
+
+    res.extend(quote! {
+        impl #impl_generics #name #ty_generics #bounds {
+            /// Descriptions of fields in the order of their serialized bytes.
+            pub const FIELDS: &'static [_struct_deser::FieldInfo] = &[#infos];
+
+            #offsets
+        }
+    });
+}
+
+// Impls (de)serialization traits of given mode
+//
+// The infallible traits don't delegate to the fallible ones, so nested types implementing only
+// the infallible traits are supported.
+fn impl_traits(
+    ast: &syn::DeriveInput,
+    body: &syn::Fields,
+    layout: &[Item],
+    mode: Mode,
+    attrs: &TypeAttrs,
+    res: &mut Tokens,
+) {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
+
+    let offending_field = offending_field(layout);
+    let (try_deser_body, try_ser_body) = impl_items(ast, layout, mode, attrs, &[], true);
+    let (try_deser_bounds, try_ser_bounds) = item_bounds(ast, layout, mode, attrs.byte_order, true);
+    let (deser_body, ser_body) = impl_items(ast, layout, mode, attrs, &[], false);
+    let (deser_bounds, ser_bounds) = item_bounds(ast, layout, mode, attrs.byte_order, false);
+    let constructor = constructor(ast, body, layout.iter().flat_map(Item::fields).collect());
+
+    let Traits {
+        try_from_bytes,
+        from_bytes,
+        try_into_bytes,
+        into_bytes,
+        method_generics,
+        ..
+    } = traits(mode);
+
+    res.extend(quote! {
+        impl #impl_generics #try_from_bytes for #name #ty_generics #try_deser_bounds {
+            fn try_from_bytes #method_generics(bytes: &[u8]) -> _core::result::Result<Self, _struct_deser::Error> {
+                if bytes.len() != <Self as _struct_deser::SerializedByteLen>::BYTE_LEN {
+                    return _core::result::Result::Err(_struct_deser::Error::InvalidLength {
+                        expected: <Self as _struct_deser::SerializedByteLen>::BYTE_LEN,
+                        actual: bytes.len(),
+                        field: #offending_field,
+                    });
                 }
-                byte_order = Some(ByteOrder::BE);
+
+                let mut reader = _struct_deser::ByteReader::new(bytes);
+                #try_deser_body
+                _core::result::Result::Ok(#constructor)
+            }
+        }
+
+        impl #impl_generics #from_bytes for #name #ty_generics #deser_bounds {
+            fn from_bytes #method_generics(bytes: &[u8]) -> Self {
+                assert_eq!(bytes.len(), <Self as _struct_deser::SerializedByteLen>::BYTE_LEN);
+
+                let mut reader = _struct_deser::ByteReader::new(bytes);
+                #deser_body
+                #constructor
             }
+        }
 
-            if word.as_ref() == "le" {
-                if byte_order == Some(ByteOrder::BE) {
-                    panic!("Conflicting byte order: you can't specify both Little and Big endian");
+        impl #impl_generics #try_into_bytes for #name #ty_generics #try_ser_bounds {
+            fn try_into_bytes #method_generics(&self, bytes: &mut [u8]) -> _core::result::Result<usize, _struct_deser::Error> {
+                if bytes.len() < <Self as _struct_deser::SerializedByteLen>::BYTE_LEN {
+                    return _core::result::Result::Err(_struct_deser::Error::BufferTooSmall {
+                        required: <Self as _struct_deser::SerializedByteLen>::BYTE_LEN,
+                        actual: bytes.len(),
+                    });
                 }
-                byte_order = Some(ByteOrder::LE);
+
+                let mut writer = _struct_deser::ByteWriter::new(&mut bytes[..<Self as _struct_deser::SerializedByteLen>::BYTE_LEN]);
+                #try_ser_body
+                _core::result::Result::Ok(<Self as _struct_deser::SerializedByteLen>::BYTE_LEN)
             }
         }
-    }
 
-    byte_order
+        impl #impl_generics #into_bytes for #name #ty_generics #ser_bounds {
+            fn into_bytes #method_generics(&self, bytes: &mut [u8]) {
+                assert_eq!(bytes.len(), <Self as _struct_deser::SerializedByteLen>::BYTE_LEN);
+
+                let mut writer = _struct_deser::ByteWriter::new(bytes);
+                #ser_body
+            }
+        }
+    });
+    res.extend(impl_to_byte_array(ast, mode));
+}
+
+// Impls `ParseBytes` and `WriteBytes` for struct with variable length
+fn impl_variable_traits(
+    ast: &syn::DeriveInput,
+    body: &syn::Fields,
+    segments: &[Segment],
+    payload: Option<&Payload>,
+    attrs: &TypeAttrs,
+    res: &mut Tokens,
+) {
+    let name = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let field_order = |field| get_field_order(field, Mode::Plain, attrs.byte_order);
+
+    // Borrowed fields determine the lifetime of the input, other structs may borrow any input
+    let borrowed = segments.iter().filter_map(|segment| match *segment {
+        Segment::Counted(Counted {
+            elem: Elem::Borrowed(ref lifetime),
+            ..
+        }) => Some(lifetime),
+        _ => None,
+    });
+    let mut parse_generics = ast.generics.clone();
+    let lifetime = match payload
+        .map(|payload| &payload.lifetime)
+        .or(borrowed.clone().next())
+    {
+        Some(lifetime) => lifetime.clone(),
+        None => {
+            let lifetime = syn::Lifetime::new("'__struct_deser_a", Span::call_site());
+            parse_generics.params.insert(
+                0,
+                syn::GenericParam::Lifetime(syn::LifetimeParam::new(lifetime.clone())),
+            );
+            lifetime
+        }
+    };
+    let (parse_impl_generics, _, _) = parse_generics.split_for_impl();
+    let (impl_generics, _, _) = ast.generics.split_for_impl();
+
+    let items = segments.iter().flat_map(Segment::items);
+    let (mut deser_bounds, mut ser_bounds) =
+        item_bounds(ast, items, Mode::Plain, attrs.byte_order, true);
+
+    let mut deser_body = Tokens::new();
+    let mut derived_lens = Tokens::new();
+    let mut ser_body = Tokens::new();
+    let mut serialized_len = quote! { Self::HEADER_LEN };
+    let derived = segments
+        .iter()
+        .filter_map(|segment| match *segment {
+            Segment::Counted(ref counted) => Some(counted.len.index),
+            Segment::Fixed(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    for segment in segments {
+        match *segment {
+            Segment::Fixed(ref layout) => {
+                let offending_field = offending_field(layout);
+                let (deser_items, ser_items) =
+                    impl_items(ast, layout, Mode::Plain, attrs, &derived, true);
+                let segment_len = layout_len(layout);
+
+                deser_body.extend(quote! {
+                    let bytes = reader.rest();
+                    if bytes.len() < #segment_len {
+                        return _core::result::Result::Err(_struct_deser::Error::InvalidLength {
+                            expected: reader.position() + #segment_len,
+                            actual: input.len(),
+                            field: #offending_field,
+                        });
+                    }
+
+                    #deser_items
+                });
+                ser_body.extend(ser_items);
+            }
+            Segment::Counted(ref counted) => {
+                let field_name = counted.field.name();
+                let field_accessor = counted.field.accessor();
+                let var = counted.field.var();
+                let len_name = counted.len.name();
+                let len_ty = &counted.len.field.ty;
+                let len_var = counted.len.var();
+                let elem_len = counted.elem.byte_len();
+
+                add_bound(
+                    &mut deser_bounds,
+                    &ast.generics,
+                    len_ty,
+                    quote! { _struct_deser::Length },
+                );
+                add_bound(
+                    &mut ser_bounds,
+                    &ast.generics,
+                    len_ty,
+                    quote! { _struct_deser::Length },
+                );
+
+                let (deser_elems, ser_elems) = match counted.elem {
+                    Elem::Borrowed(_) => (
+                        quote! { reader.try_read_bytes(byte_len)? },
+                        quote! { writer.try_write_bytes(self.#field_accessor)?; },
+                    ),
+                    Elem::Bytes => (
+                        quote! { reader.try_read_bytes(byte_len)?.to_vec() },
+                        quote! { writer.try_write_bytes(&self.#field_accessor)?; },
+                    ),
+                    Elem::Items(ty) => {
+                        let order = field_order(counted.field);
+                        let deser_elem = read_call(order, ty, true);
+                        let ser_elem = write_call(order, ty, quote! { elem }, true);
+
+                        let (deser_bound, ser_bound) = match order {
+                            FieldOrder::None => (
+                                quote! { _struct_deser::TryFromBytes },
+                                quote! { _struct_deser::TryIntoBytes },
+                            ),
+                            FieldOrder::Fixed(_) | FieldOrder::Inherited => (
+                                quote! { _struct_deser::ordered::TryFromBytes },
+                                quote! { _struct_deser::ordered::TryIntoBytes },
+                            ),
+                        };
+                        add_bound(&mut deser_bounds, &ast.generics, ty, deser_bound);
+                        add_bound(&mut ser_bounds, &ast.generics, ty, ser_bound);
+
+                        (
+                            quote! {
+                                (0..count)
+                                    .map(|_| #deser_elem)
+                                    .collect::<_core::result::Result<_, _>>()
+                                    .map_err(|err: _struct_deser::Error| err.in_field(#field_name))?
+                            },
+                            quote! {
+                                for elem in self.#field_accessor.iter() {
+                                    #ser_elem.map_err(|err| err.in_field(#field_name))?;
+                                }
+                            },
+                        )
+                    }
+                };
+
+                deser_body.extend(quote! {
+                    let count = _struct_deser::Length::to_len(&#len_var).unwrap_or(usize::MAX);
+                    let byte_len = count.checked_mul(#elem_len).unwrap_or(usize::MAX);
+                    if reader.remaining() < byte_len {
+                        return _core::result::Result::Err(_struct_deser::Error::InvalidLength {
+                            expected: reader.position().saturating_add(byte_len),
+                            actual: input.len(),
+                            field: _core::option::Option::Some(#field_name),
+                        });
+                    }
+
+                    let #var = #deser_elems;
+                });
+                derived_lens.extend(quote! {
+                    let #len_var: #len_ty = _struct_deser::Length::from_len(self.#field_accessor.len())
+                        .ok_or(_struct_deser::Error::OutOfRange {
+                            field: _core::option::Option::Some(#len_name),
+                        })?;
+                });
+                ser_body.extend(ser_elems);
+                serialized_len.extend(quote! { + self.#field_accessor.len() * #elem_len });
+            }
+        }
+    }
+
+    let mut payload_field = None;
+    if let Some(payload) = payload {
+        let field_accessor = payload.field.accessor();
+        let var = payload.field.var();
+
+        deser_body.extend(quote! {
+            let #var = reader.rest();
+            reader.try_skip(#var.len())?;
+        });
+        ser_body.extend(quote! {
+            writer.try_write_bytes(self.#field_accessor)?;
+        });
+        serialized_len.extend(quote! { + self.#field_accessor.len() });
+        payload_field = Some(payload.field);
+    }
+
+    let fields = segments
+        .iter()
+        .flat_map(|segment| match *segment {
+            Segment::Fixed(ref layout) => layout.iter().flat_map(Item::fields).collect(),
+            Segment::Counted(ref counted) => vec![counted.field],
+        })
+        .chain(payload_field)
+        .collect();
+    let constructor = constructor(ast, body, fields);
+
+    res.extend(quote! {
+        impl #parse_impl_generics _struct_deser::ParseBytes<#lifetime> for #name #ty_generics #deser_bounds {
+            fn parse(input: &#lifetime [u8]) -> _core::result::Result<(Self, usize), _struct_deser::Error> {
+                let mut reader = _struct_deser::ByteReader::new(input);
+                #deser_body
+                _core::result::Result::Ok((#constructor, reader.position()))
+            }
+        }
+
+        impl #impl_generics _struct_deser::WriteBytes for #name #ty_generics #ser_bounds {
+            fn serialized_len(&self) -> usize {
+                #serialized_len
+            }
+
+            fn write_bytes(&self, output: &mut [u8]) -> _core::result::Result<usize, _struct_deser::Error> {
+                #derived_lens
+                let required = _struct_deser::WriteBytes::serialized_len(self);
+                if output.len() < required {
+                    return _core::result::Result::Err(_struct_deser::Error::BufferTooSmall {
+                        required: required,
+                        actual: output.len(),
+                    });
+                }
+
+                let mut writer = _struct_deser::ByteWriter::new(output);
+                #ser_body
+                _core::result::Result::Ok(writer.position())
+            }
+        }
+    });
+}
+
+// Creates expression evaluating to the name of the first field that doesn't fit into `bytes`
+fn offending_field(layout: &[Item]) -> Tokens {
+    let mut offending_field = quote! { _core::option::Option::None };
+    for (item_no, item) in layout.iter().enumerate().rev() {
+        let field_name = match item.first_field() {
+            Some(field) => {
+                let name = field.name();
+                quote! { _core::option::Option::Some(#name) }
+            }
+            None => quote! { _core::option::Option::None },
+        };
+        let item_len = item.byte_len();
+        let item_offset = layout_len(&layout[..item_no]);
+
+        offending_field = quote! {
+            if bytes.len() < #item_offset + #item_len {
+                #field_name
+            } else {
+                #offending_field
+            }
+        };
+    }
+
+    offending_field
+}
+
+// Creates statements deserializing items from `reader` into local variables and statements
+// serializing fields of `self` into `writer`
+//
+// Fallible statements propagate errors using `?`, infallible ones panic. Fields with indices in
+// `derived` are serialized from local variables instead of `self`.
+fn impl_items(
+    ast: &syn::DeriveInput,
+    layout: &[Item],
+    mode: Mode,
+    attrs: &TypeAttrs,
+    derived: &[usize],
+    fallible: bool,
+) -> (Tokens, Tokens) {
+    let name = &ast.ident;
+    let msb_first = attrs.bit_order == BitOrder::Msb;
+    let read_bytes = cursor_method("read_bytes", fallible);
+    let skip = cursor_method("skip", fallible);
+    let write_bytes = cursor_method("write_bytes", fallible);
+    let fill = cursor_method("fill", fallible);
+    let propagate = if fallible {
+        quote! { ? }
+    } else {
+        Tokens::new()
+    };
+    let fail = |err: Tokens, action: &str| {
+        if fallible {
+            quote! { return _core::result::Result::Err(#err); }
+        } else {
+            quote! { panic!("failed to {} {}: {}", #action, stringify!(#name), #err); }
+        }
+    };
+
+    let mut deser_body = Tokens::new();
+    let mut ser_body = Tokens::new();
+    for item in layout {
+        let item_len = item.byte_len();
+
+        match *item {
+            Item::Field(field) => {
+                let field_name = field.name();
+                let field_accessor = field.accessor();
+                let var = field.var();
+
+                let value = if derived.contains(&field.index) {
+                    quote! { &#var }
+                } else {
+                    quote! { &self.#field_accessor }
+                };
+
+                let order = get_field_order(field, mode, attrs.byte_order);
+                let ty = &field.field.ty;
+                let deser_impl = read_call(order, ty, fallible);
+                let in_field = if fallible {
+                    quote! { .map_err(|err| err.in_field(#field_name))? }
+                } else {
+                    Tokens::new()
+                };
+                let invalid_constant = fail(
+                    quote! {
+                        _struct_deser::Error::InvalidConstant {
+                            type_name: stringify!(#name),
+                            field: _core::option::Option::Some(#field_name),
+                        }
+                    },
+                    "deserialize",
+                );
+
+                match field.attrs.constant {
+                    None => {
+                        let ser_impl = write_call(order, ty, value, fallible);
+
+                        deser_body.extend(quote! {
+                            let #var = #deser_impl #in_field;
+                        });
+                        ser_body.extend(quote! {
+                            #ser_impl #in_field;
+                        });
+                    }
+                    Some(Constant::Magic(ref magic)) => {
+                        let magic = byte_str(magic);
+
+                        deser_body.extend(quote! {
+                            if reader.rest()[..#item_len] != #magic[..] {
+                                #invalid_constant
+                            }
+                            let #var = #deser_impl #in_field;
+                        });
+                        ser_body.extend(quote! {
+                            writer.#write_bytes(#magic)#propagate;
+                        });
+                    }
+                    Some(Constant::Value(ref value)) => {
+                        let ser_impl = write_call(order, ty, quote! { &value }, fallible);
+
+                        deser_body.extend(quote! {
+                            let #var: #ty = #deser_impl #in_field;
+                            if #var != (#value) {
+                                #invalid_constant
+                            }
+                        });
+                        ser_body.extend(quote! {
+                            {
+                                let value: #ty = #value;
+                                #ser_impl #in_field;
+                            }
+                        });
+                    }
+                }
+            }
+            Item::Bits {
+                ref fields,
+                byte_len: group_len,
+            } => {
+                let group_var = format_ident!("__bits_{}", fields[0].0.index);
+                let mut deser_fields = Tokens::new();
+                let mut ser_fields = Tokens::new();
+
+                for (field, shift, mask) in bit_positions(fields, group_len, msb_first) {
+                    let ty = &field.field.ty;
+                    let field_name = field.name();
+                    let field_accessor = field.accessor();
+                    let var = field.var();
+                    let out_of_range = fail(
+                        quote! {
+                            _struct_deser::Error::OutOfRange {
+                                field: _core::option::Option::Some(#field_name),
+                            }
+                        },
+                        "serialize",
+                    );
+
+                    deser_fields.extend(quote! {
+                        let #var = <#ty as _struct_deser::BitField>::from_bits((#group_var >> #shift) & #mask);
+                    });
+                    ser_fields.extend(quote! {
+                        let bits = <#ty as _struct_deser::BitField>::into_bits(&self.#field_accessor);
+                        if bits & !#mask != 0 {
+                            #out_of_range
+                        }
+                        #group_var |= bits << #shift;
+                    });
+                }
+
+                deser_body.extend(quote! {
+                    let #group_var = _struct_deser::bits::read_group(reader.#read_bytes(#item_len)#propagate, #msb_first);
+                    #deser_fields
+                });
+                ser_body.extend(quote! {
+                    let mut #group_var: u128 = 0;
+                    #ser_fields
+                    let mut group = [0; #item_len];
+                    _struct_deser::bits::write_group(&mut group, #group_var, #msb_first);
+                    writer.#write_bytes(&group)#propagate;
+                });
+            }
+            Item::Magic(ref magic) => {
+                let magic = byte_str(magic);
+                let invalid_constant = fail(
+                    quote! {
+                        _struct_deser::Error::InvalidConstant {
+                            type_name: stringify!(#name),
+                            field: _core::option::Option::None,
+                        }
+                    },
+                    "deserialize",
+                );
+
+                deser_body.extend(quote! {
+                    if reader.#read_bytes(#item_len)#propagate != &#magic[..] {
+                        #invalid_constant
+                    }
+                });
+                ser_body.extend(quote! {
+                    writer.#write_bytes(#magic)#propagate;
+                });
+            }
+            Item::Pad {
+                fill: byte, verify, ..
+            } => {
+                if verify {
+                    let invalid_constant = fail(
+                        quote! {
+                            _struct_deser::Error::InvalidConstant {
+                                type_name: stringify!(#name),
+                                field: _core::option::Option::None,
+                            }
+                        },
+                        "deserialize",
+                    );
+
+                    deser_body.extend(quote! {
+                        if reader.#read_bytes(#item_len)#propagate.iter().any(|&byte| byte != #byte) {
+                            #invalid_constant
+                        }
+                    });
+                } else {
+                    deser_body.extend(quote! {
+                        reader.#skip(#item_len)#propagate;
+                    });
+                }
+
+                ser_body.extend(quote! {
+                    writer.#fill(#item_len, #byte)#propagate;
+                });
+            }
+        }
+    }
+
+    (deser_body, ser_body)
+}
+
+// Name of the method of `ByteReader` or `ByteWriter`, the fallible ones are prefixed with `try_`
+fn cursor_method(name: &str, fallible: bool) -> syn::Ident {
+    if fallible {
+        format_ident!("try_{}", name)
+    } else {
+        format_ident!("{}", name)
+    }
+}
+
+// Computes the shift and mask of each bit field in a group
+fn bit_positions<'a>(
+    fields: &[(StructField<'a>, u32)],
+    group_len: usize,
+    msb_first: bool,
+) -> Vec<(StructField<'a>, u32, Tokens)> {
+    let mut bit_offset = 0;
+    let mut positions = Vec::new();
+    for &(field, width) in fields {
+        let shift = if msb_first {
+            group_len as u32 * 8 - bit_offset - width
+        } else {
+            bit_offset
+        };
+        let mask = u128_literal(u128::MAX >> (128 - width));
+
+        positions.push((field, shift, mask));
+        bit_offset += width;
+    }
+
+    positions
+}
+
+// Creates definitions of view types and their impls
+//
+// The definitions can't be wrapped in a block with the impls, otherwise they couldn't be named.
+fn impl_view(ast: &syn::DeriveInput, layout: &[Item], attrs: &TypeAttrs) -> (Tokens, Tokens) {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let view = format_ident!("{}View", name);
+    let view_mut = format_ident!("{}ViewMut", name);
+    let msb_first = attrs.bit_order == BitOrder::Msb;
+    let byte_len = quote! { <#name as _struct_deser::SerializedByteLen>::BYTE_LEN };
+
+    let mut getters = Tokens::new();
+    let mut setters = Tokens::new();
+    let mut offset = quote! { 0 };
+    for item in layout {
+        let item_len = item.byte_len();
+        let byte_slice = quote! { self.bytes[(#offset)..(#offset + #item_len)] };
+
+        match *item {
+            Item::Field(field) => {
+                let field_vis = &field.field.vis;
+                let field_name = field.name();
+                let ty = &field.field.ty;
+                let getter = field.accessor();
+                let setter = format_ident!("set_{}", field_name);
+                let order = get_field_order(field, Mode::Plain, attrs.byte_order);
+                let deser_impl = deser_call(order, quote! { &#byte_slice });
+                let ser_impl = ser_call(order, quote! { &value }, quote! { &mut #byte_slice });
+
+                getters.extend(quote! {
+                    #field_vis fn #getter(&self) -> #ty {
+                        match #deser_impl {
+                            _core::result::Result::Ok(val) => val,
+                            _core::result::Result::Err(err) => panic!("failed to deserialize {}::{}: {}", stringify!(#name), #field_name, err),
+                        }
+                    }
+                });
+
+                // Constants can't be changed
+                if field.attrs.constant.is_none() {
+                    setters.extend(quote! {
+                        #field_vis fn #setter(&mut self, value: #ty) {
+                            if let _core::result::Result::Err(err) = #ser_impl {
+                                panic!("failed to serialize {}::{}: {}", stringify!(#name), #field_name, err);
+                            }
+                        }
+                    });
+                }
+            }
+            Item::Bits {
+                ref fields,
+                byte_len: group_len,
+            } => {
+                for (field, shift, mask) in bit_positions(fields, group_len, msb_first) {
+                    let field_vis = &field.field.vis;
+                    let field_name = field.name();
+                    let ty = &field.field.ty;
+                    let getter = field.accessor();
+                    let setter = format_ident!("set_{}", field_name);
+
+                    getters.extend(quote! {
+                        #field_vis fn #getter(&self) -> #ty {
+                            let group = _struct_deser::bits::read_group(&#byte_slice, #msb_first);
+                            <#ty as _struct_deser::BitField>::from_bits((group >> #shift) & #mask)
+                        }
+                    });
+                    setters.extend(quote! {
+                        #field_vis fn #setter(&mut self, value: #ty) {
+                            let bits = <#ty as _struct_deser::BitField>::into_bits(&value);
+                            if bits & !#mask != 0 {
+                                let err = _struct_deser::Error::OutOfRange {
+                                    field: _core::option::Option::Some(#field_name),
+                                };
+                                panic!("failed to serialize {}::{}: {}", stringify!(#name), #field_name, err);
+                            }
+
+                            let group = _struct_deser::bits::read_group(&#byte_slice, #msb_first);
+                            let group = (group & !(#mask << #shift)) | (bits << #shift);
+                            _struct_deser::bits::write_group(&mut #byte_slice, group, #msb_first);
+                        }
+                    });
+                }
+            }
+            Item::Magic(_) | Item::Pad { .. } => (),
+        }
+
+        offset.extend(quote! { + #item_len });
+    }
+
+    let view_doc = format!(
+        "View of `{}` serialized in bytes, which decodes the fields on access.",
+        name
+    );
+    let view_mut_doc = format!(
+        "Mutable view of `{}` serialized in bytes, which decodes and encodes the fields on access.",
+        name
+    );
+    let defs = quote! {
+        #[doc = #view_doc]
+        #[derive(Debug, Copy, Clone)]
+        #vis struct #view<'a> {
+            bytes: &'a [u8; {
+                extern crate struct_deser as _struct_deser;
+                #byte_len
+            }],
+        }
+
+        #[doc = #view_mut_doc]
+        #[derive(Debug)]
+        #vis struct #view_mut<'a> {
+            bytes: &'a mut [u8; {
+                extern crate struct_deser as _struct_deser;
+                #byte_len
+            }],
+        }
+    };
+
+    // Lints about method names don't apply because the methods are named after the fields
+    let impls = quote! {
+        impl #name {
+            /// Creates a view of serialized struct.
+            #vis fn view<'a>(bytes: &'a [u8; #byte_len]) -> #view<'a> {
+                #view { bytes: bytes }
+            }
+
+            /// Creates a mutable view of serialized struct.
+            #vis fn view_mut<'a>(bytes: &'a mut [u8; #byte_len]) -> #view_mut<'a> {
+                #view_mut { bytes: bytes }
+            }
+        }
+
+        #[allow(clippy::len_without_is_empty, clippy::wrong_self_convention)]
+        impl<'a> #view<'a> {
+            #getters
+
+            /// Returns the viewed bytes.
+            #vis fn as_bytes(&self) -> &'a [u8; #byte_len] {
+                self.bytes
+            }
+        }
+
+        #[allow(clippy::len_without_is_empty, clippy::wrong_self_convention)]
+        impl<'a> #view_mut<'a> {
+            #getters
+            #setters
+
+            /// Returns immutable view of the same bytes.
+            #vis fn as_view(&self) -> #view {
+                #view { bytes: self.bytes }
+            }
+
+            /// Returns the viewed bytes.
+            #vis fn as_bytes(&self) -> &[u8; #byte_len] {
+                self.bytes
+            }
+
+            /// Returns the viewed bytes mutably.
+            #vis fn as_bytes_mut(&mut self) -> &mut [u8; #byte_len] {
+                self.bytes
+            }
+        }
+
+        impl<'a> _core::convert::From<#view<'a>> for #name {
+            fn from(view: #view<'a>) -> Self {
+                <#name as _struct_deser::FromBytes>::from_bytes(view.bytes)
+            }
+        }
+
+        impl<'a> _core::convert::From<#view_mut<'a>> for #name {
+            fn from(view: #view_mut<'a>) -> Self {
+                <#name as _struct_deser::FromBytes>::from_bytes(view.bytes)
+            }
+        }
+
+        impl<'a> _struct_deser::FromRecord<'a> for #view<'a> {
+            const RECORD_LEN: usize = #byte_len;
+
+            fn from_record(bytes: &'a [u8]) -> Self {
+                match <&'a [u8; #byte_len] as _core::convert::TryFrom<&'a [u8]>>::try_from(bytes) {
+                    _core::result::Result::Ok(bytes) => #view { bytes: bytes },
+                    _core::result::Result::Err(_) => panic!("invalid length of {} record", stringify!(#name)),
+                }
+            }
+        }
+
+        impl<'a> _struct_deser::FromRecordMut<'a> for #view_mut<'a> {
+            const RECORD_LEN: usize = #byte_len;
+
+            fn from_record_mut(bytes: &'a mut [u8]) -> Self {
+                match <&'a mut [u8; #byte_len] as _core::convert::TryFrom<&'a mut [u8]>>::try_from(bytes) {
+                    _core::result::Result::Ok(bytes) => #view_mut { bytes: bytes },
+                    _core::result::Result::Err(_) => panic!("invalid length of {} record", stringify!(#name)),
+                }
+            }
+        }
+    };
+
+    (defs, impls)
+}
+
+// Creates where clauses of deserialization and serialization impls
+//
+// Fields of fallible impls are bound even if they don't depend on type parameters, so the impls
+// are only available if all of the fields implement the fallible traits.
+fn item_bounds<'a, 'b: 'a, I>(
+    ast: &syn::DeriveInput,
+    layout: I,
+    mode: Mode,
+    default_byte_order: Option<ByteOrder>,
+    fallible: bool,
+) -> (syn::WhereClause, syn::WhereClause)
+where
+    I: IntoIterator<Item = &'a Item<'b>> + Clone,
+{
+    let field_order = |field| get_field_order(field, mode, default_byte_order);
+    let deser_bound = |field| match (field_order(field), fallible) {
+        (FieldOrder::None, true) => quote! { _struct_deser::TryFromBytes },
+        (FieldOrder::None, false) => quote! { _struct_deser::FromBytes },
+        (_, true) => quote! { _struct_deser::ordered::TryFromBytes },
+        (_, false) => quote! { _struct_deser::ordered::FromBytes },
+    };
+    let ser_bound = |field| match (field_order(field), fallible) {
+        (FieldOrder::None, true) => quote! { _struct_deser::TryIntoBytes },
+        (FieldOrder::None, false) => quote! { _struct_deser::IntoBytes },
+        (_, true) => quote! { _struct_deser::ordered::TryIntoBytes },
+        (_, false) => quote! { _struct_deser::ordered::IntoBytes },
+    };
+
+    let mut deser_bounds = add_bounds(&ast.generics, layout.clone(), deser_bound);
+    let mut ser_bounds = add_bounds(&ast.generics, layout.clone(), ser_bound);
+    if fallible {
+        add_concrete_bounds(
+            &mut deser_bounds,
+            &ast.generics,
+            layout.clone(),
+            deser_bound,
+        );
+        add_concrete_bounds(&mut ser_bounds, &ast.generics, layout, ser_bound);
+    }
+
+    (deser_bounds, ser_bounds)
+}
+
+// Creates expression constructing the struct from local variables holding deserialized fields
+fn constructor(ast: &syn::DeriveInput, body: &syn::Fields, mut fields: Vec<StructField>) -> Tokens {
+    use syn::Fields;
+
+    // Tuple structs need the fields in order
+    fields.sort_by_key(|field| field.index);
+
+    let name = &ast.ident;
+    let mut vars = Tokens::new();
+    for field in fields {
+        append_field_init(&mut vars, field);
+    }
+
+    match *body {
+        Fields::Named(_) => quote! { #name { #vars } },
+        Fields::Unnamed(_) => quote! { #name(#vars) },
+        Fields::Unit => quote! { #name },
+    }
+}
+
+// Appends initialization of the field from its local variable to struct constructor
+fn append_field_init(tokens: &mut Tokens, field: StructField) {
+    let var = field.var();
+    tokens.extend(match field.field.ident {
+        Some(ref ident) => quote! { #ident: #var, },
+        None => quote! { #var, },
+    });
+}
+
+// Creates `u128` literal with explicit suffix
+fn u128_literal(value: u128) -> Tokens {
+    proc_macro2::Literal::u128_suffixed(value).into_token_stream()
+}
+
+// Paths of traits implemented in given mode and generics of their methods
+struct Traits {
+    try_from_bytes: Tokens,
+    from_bytes: Tokens,
+    try_into_bytes: Tokens,
+    into_bytes: Tokens,
+    method_generics: Tokens,
+    call_generics: Tokens,
+}
+
+fn traits(mode: Mode) -> Traits {
+    match mode {
+        Mode::Plain => Traits {
+            try_from_bytes: quote! { _struct_deser::TryFromBytes },
+            from_bytes: quote! { _struct_deser::FromBytes },
+            try_into_bytes: quote! { _struct_deser::TryIntoBytes },
+            into_bytes: quote! { _struct_deser::IntoBytes },
+            method_generics: quote! {},
+            call_generics: quote! {},
+        },
+        Mode::Ordered => Traits {
+            try_from_bytes: quote! { _struct_deser::TryFromBytesOrdered },
+            from_bytes: quote! { _struct_deser::FromBytesOrdered },
+            try_into_bytes: quote! { _struct_deser::TryIntoBytesOrdered },
+            into_bytes: quote! { _struct_deser::IntoBytesOrdered },
+            method_generics: quote! { <__BO: _struct_deser::byteorder::ByteOrder> },
+            call_generics: quote! { ::<__BO> },
+        },
+    }
+}
+
+// Impls infallible traits by delegating to the fallible ones
+fn impl_infallible(
+    ast: &syn::DeriveInput,
+    mode: Mode,
+    deser_bounds: &syn::WhereClause,
+    ser_bounds: &syn::WhereClause,
+) -> Tokens {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
+    let Traits {
+        try_from_bytes,
+        from_bytes,
+        try_into_bytes,
+        into_bytes,
+        method_generics,
+        call_generics,
+    } = traits(mode);
+
+    let mut res = quote! {
+        impl #impl_generics #from_bytes for #name #ty_generics #deser_bounds {
+            fn from_bytes #method_generics(bytes: &[u8]) -> Self {
+                match <Self as #try_from_bytes>::try_from_bytes #call_generics(bytes) {
+                    _core::result::Result::Ok(val) => val,
+                    _core::result::Result::Err(err) => panic!("failed to deserialize {}: {}", stringify!(#name), err),
+                }
+            }
+        }
+
+        impl #impl_generics #into_bytes for #name #ty_generics #ser_bounds {
+            fn into_bytes #method_generics(&self, bytes: &mut [u8]) {
+                assert_eq!(bytes.len(), <Self as _struct_deser::SerializedByteLen>::BYTE_LEN);
+                if let _core::result::Result::Err(err) = <Self as #try_into_bytes>::try_into_bytes #call_generics(self, bytes) {
+                    panic!("failed to serialize {}: {}", stringify!(#name), err);
+                }
+            }
+        }
+    };
+    res.extend(impl_to_byte_array(ast, mode));
+
+    res
+}
+
+// Impls `ToByteArray` if the type implements the plain traits
+fn impl_to_byte_array(ast: &syn::DeriveInput, mode: Mode) -> Tokens {
+    let name = &ast.ident;
+
+    // Array length can't depend on generic parameters
+    if mode == Mode::Plain && ast.generics.params.is_empty() {
+        quote! {
+            impl _struct_deser::ToByteArray for #name {
+                type Array = [u8; <#name as _struct_deser::SerializedByteLen>::BYTE_LEN];
+            }
+        }
+    } else {
+        Tokens::new()
+    }
+}
+
+// Impls the helper traits used for fields with byte order
+//
+// Types which aren't ordered ignore the byte order, so they can be nested in structs with default
+// byte order. The impls are only available if the type implements the corresponding traits.
+fn impl_ordered_helpers(ast: &syn::DeriveInput, ordered: bool) -> Tokens {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
+    let mode = if ordered { Mode::Ordered } else { Mode::Plain };
+    let Traits {
+        try_from_bytes,
+        from_bytes,
+        try_into_bytes,
+        into_bytes,
+        call_generics,
+        ..
+    } = traits(mode);
+
+    let bounds = |bound: &Tokens| {
+        let mut where_clause = where_clause(&ast.generics);
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { for<'__struct_deser> #name #ty_generics: #bound });
+        where_clause
+    };
+    let try_deser_bounds = bounds(&try_from_bytes);
+    let deser_bounds = bounds(&from_bytes);
+    let try_ser_bounds = bounds(&try_into_bytes);
+    let ser_bounds = bounds(&into_bytes);
+
+    quote! {
+        impl #impl_generics _struct_deser::ordered::TryFromBytes for #name #ty_generics #try_deser_bounds {
+            fn try_from_bytes<__BO: _struct_deser::byteorder::ByteOrder>(bytes: &[u8]) -> _core::result::Result<Self, _struct_deser::Error> {
+                <Self as #try_from_bytes>::try_from_bytes #call_generics(bytes)
+            }
+        }
+
+        impl #impl_generics _struct_deser::ordered::FromBytes for #name #ty_generics #deser_bounds {
+            fn from_bytes<__BO: _struct_deser::byteorder::ByteOrder>(bytes: &[u8]) -> Self {
+                <Self as #from_bytes>::from_bytes #call_generics(bytes)
+            }
+        }
+
+        impl #impl_generics _struct_deser::ordered::TryIntoBytes for #name #ty_generics #try_ser_bounds {
+            fn try_into_bytes<__BO: _struct_deser::byteorder::ByteOrder>(&self, bytes: &mut [u8]) -> _core::result::Result<usize, _struct_deser::Error> {
+                <Self as #try_into_bytes>::try_into_bytes #call_generics(self, bytes)
+            }
+        }
+
+        impl #impl_generics _struct_deser::ordered::IntoBytes for #name #ty_generics #ser_bounds {
+            fn into_bytes<__BO: _struct_deser::byteorder::ByteOrder>(&self, bytes: &mut [u8]) {
+                <Self as #into_bytes>::into_bytes #call_generics(self, bytes)
+            }
+        }
+    }
+}
+
+// Type implementing `ByteOrder` used by fields with known byte order
+fn order_param(order: FieldOrder) -> Option<Tokens> {
+    match order {
+        FieldOrder::None => None,
+        FieldOrder::Fixed(bo) => Some(quote! { _struct_deser::byteorder::#bo }),
+        FieldOrder::Inherited => Some(quote! { __BO }),
+    }
+}
+
+// Expression deserializing the next value of type `ty` from `reader` using byte order
+//
+// Fields with byte order go through the helper traits, which are implemented for `u8` too.
+// Fallible expressions evaluate to `Result`.
+fn read_call(order: FieldOrder, ty: &syn::Type, fallible: bool) -> Tokens {
+    match (order_param(order), fallible) {
+        (None, true) => quote! { reader.try_read::<#ty>() },
+        (None, false) => quote! { reader.read::<#ty>() },
+        (Some(bo), true) => quote! {
+            <#ty as _struct_deser::ordered::TryFromBytes>::try_from_bytes::<#bo>(
+                reader.try_read_bytes(<#ty as _struct_deser::SerializedByteLen>::BYTE_LEN)?
+            )
+        },
+        (Some(bo), false) => quote! {
+            <#ty as _struct_deser::ordered::FromBytes>::from_bytes::<#bo>(
+                reader.read_bytes(<#ty as _struct_deser::SerializedByteLen>::BYTE_LEN)
+            )
+        },
+    }
+}
+
+// Expression serializing a value (reference) of type `ty` into `writer` using byte order
+fn write_call(order: FieldOrder, ty: &syn::Type, value: Tokens, fallible: bool) -> Tokens {
+    match (order_param(order), fallible) {
+        (None, true) => quote! { writer.try_write::<#ty>(#value) },
+        (None, false) => quote! { writer.write::<#ty>(#value) },
+        (Some(bo), true) => quote! {
+            writer.try_write_with(<#ty as _struct_deser::SerializedByteLen>::BYTE_LEN, |bytes| {
+                <#ty as _struct_deser::ordered::TryIntoBytes>::try_into_bytes::<#bo>(#value, bytes)
+            })
+        },
+        (Some(bo), false) => quote! {
+            writer.write_with(<#ty as _struct_deser::SerializedByteLen>::BYTE_LEN, |bytes| {
+                <#ty as _struct_deser::ordered::IntoBytes>::into_bytes::<#bo>(#value, bytes)
+            })
+        },
+    }
+}
+
+// Expression deserializing a value from `bytes` using byte order
+fn deser_call(order: FieldOrder, bytes: Tokens) -> Tokens {
+    match order_param(order) {
+        None => quote! { _struct_deser::TryFromBytes::try_from_bytes(#bytes) },
+        Some(bo) => quote! {
+            _struct_deser::ordered::TryFromBytes::try_from_bytes::<#bo>(#bytes)
+        },
+    }
+}
+
+// Expression serializing a value (reference) into `bytes` using byte order
+fn ser_call(order: FieldOrder, value: Tokens, bytes: Tokens) -> Tokens {
+    match order_param(order) {
+        None => quote! { _struct_deser::TryIntoBytes::try_into_bytes(#value, #bytes) },
+        Some(bo) => quote! {
+            _struct_deser::ordered::TryIntoBytes::try_into_bytes::<#bo>(#value, #bytes)
+        },
+    }
+}
+
+// Expression computing discriminant of `variant` the same way Rust does
+fn discriminant_value(variant: &syn::Variant, prev: Option<Tokens>) -> Tokens {
+    match (&variant.discriminant, prev) {
+        (Some((_, value)), _) => quote! { #value },
+        (None, Some(prev)) => quote! { (#prev) + 1 },
+        (None, None) => quote! { 0 },
+    }
+}
+
+// Impls traits for C-like enums, which are (de)serialized as their discriminant
+fn impl_enum_deser(
+    ast: &syn::DeriveInput,
+    attrs: &TypeAttrs,
+    variants: &[&syn::Variant],
+    repr: &syn::Type,
+) -> syn::Result<Tokens> {
+    use syn::Fields;
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
+    let where_clause = where_clause(&ast.generics);
+    let ordered = attrs.ordered;
+
+    let mut res = quote! {
+        impl #impl_generics _struct_deser::SerializedByteLen for #name #ty_generics #where_clause {
+            const BYTE_LEN: usize = <#repr as _struct_deser::SerializedByteLen>::BYTE_LEN;
+        }
+    };
+
+    if !ordered && attrs.byte_order.is_none() {
+        let note = format!(
+            "Discriminant of `{}` has no byte order, specify it using `#[struct_deser(byte_order = \"...\")]`",
+            name
+        );
+        warn_missing_byte_order(repr, &note, 0, &mut res);
+    }
+
+    let mut deser_arms = Tokens::new();
+    let mut ser_arms = Tokens::new();
+    let mut other = None;
+    let mut prev_discriminant = None;
+    for variant in variants {
+        let variant_name = &variant.ident;
+
+        if is_other(&variant.attrs)? {
+            match variant.fields {
+                Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => (),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "The catch-all variant must hold exactly one value - the discriminant",
+                    ))
+                }
+            }
+            if other.is_some() {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "Only one variant can be marked as `#[struct_deser(other)]`",
+                ));
+            }
+
+            ser_arms.extend(quote! { #name::#variant_name(value) => value, });
+            other = Some(variant_name);
+            // The catch-all variant still takes a discriminant, so it has to be counted
+            prev_discriminant = Some(discriminant_value(variant, prev_discriminant.take()));
+            continue;
+        }
+
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Only unit variants and one catch-all variant are supported",
+            ));
+        }
+
+        let discriminant =
+            format_ident!("__DISCRIMINANT_{}", variant_name, span = Span::call_site());
+        let value = discriminant_value(variant, prev_discriminant.take());
+        res.extend(quote! {
+            #[allow(non_upper_case_globals)]
+            const #discriminant: #repr = #value;
+        });
+        deser_arms.extend(quote! { #discriminant => #name::#variant_name, });
+        ser_arms.extend(quote! { #name::#variant_name => #discriminant, });
+        prev_discriminant = Some(quote! { #discriminant });
+    }
+
+    let unknown = match other {
+        Some(other) => quote! { value => #name::#other(value), },
+        None => quote! {
+            _ => return _core::result::Result::Err(_struct_deser::Error::UnknownDiscriminant {
+                type_name: stringify!(#name),
+                field: _core::option::Option::None,
+            }),
+        },
+    };
+
+    let mut modes = Vec::new();
+    if !ordered || attrs.byte_order.is_some() {
+        modes.push(Mode::Plain);
+    }
+    if ordered {
+        modes.push(Mode::Ordered);
+    }
+
+    for mode in modes {
+        let order = get_default_order(mode, attrs.byte_order);
+        let deser = deser_call(order, quote! { bytes });
+        let ser = ser_call(order, quote! { &value }, quote! { bytes });
+        let Traits {
+            try_from_bytes,
+            try_into_bytes,
+            method_generics,
+            ..
+        } = traits(mode);
+
+        res.extend(quote! {
+            impl #impl_generics #try_from_bytes for #name #ty_generics #where_clause {
+                fn try_from_bytes #method_generics(bytes: &[u8]) -> _core::result::Result<Self, _struct_deser::Error> {
+                    let value: #repr = #deser?;
+                    _core::result::Result::Ok(match value {
+                        #deser_arms
+                        #unknown
+                    })
+                }
+            }
+
+            impl #impl_generics #try_into_bytes for #name #ty_generics #where_clause {
+                fn try_into_bytes #method_generics(&self, bytes: &mut [u8]) -> _core::result::Result<usize, _struct_deser::Error> {
+                    let value: #repr = match *self {
+                        #ser_arms
+                    };
+                    #ser
+                }
+            }
+        });
+        res.extend(impl_infallible(ast, mode, &where_clause, &where_clause));
+    }
+    res.extend(impl_ordered_helpers(ast, ordered));
+
+    Ok(wrap_impls(res))
+}
+
+// Impls traits for enums with each variant holding a type implementing `Identifier`
+//
+// These are (de)serialized as the identifier followed by the value of the variant.
+fn impl_tagged_enum_deser(
+    ast: &syn::DeriveInput,
+    attrs: &TypeAttrs,
+    variants: &[&syn::Variant],
+) -> syn::Result<Tokens> {
+    use syn::Fields;
+
+    let name = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let order = get_default_order(Mode::Plain, attrs.byte_order);
+
+    let mut fields = Vec::new();
+    for variant in variants {
+        match variant.fields {
+            Fields::Unnamed(ref variant_fields) if variant_fields.unnamed.len() == 1 => {
+                fields.push(&variant_fields.unnamed[0])
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "Enums must either specify representation of discriminant using `#[struct_deser(repr = \"...\")]` or each variant must hold exactly one value",
+                ))
+            }
+        }
+    }
+
+    let first_ty = &fields[0].ty;
+    let id_ty = quote! { <#first_ty as _struct_deser::Identifier>::IdentifierType };
+    let id_len = quote! { <#id_ty as _struct_deser::SerializedByteLen>::BYTE_LEN };
+
+    // Parsed values may borrow from input, so the lifetime of the enum is used if there's one
+    let mut parse_generics = ast.generics.clone();
+    if parse_generics.lifetimes().next().is_none() {
+        let lifetime = syn::Lifetime::new("'__struct_deser_a", Span::call_site());
+        parse_generics.params.insert(
+            0,
+            syn::GenericParam::Lifetime(syn::LifetimeParam::new(lifetime)),
+        );
+    }
+    let lifetime = match parse_generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => unreachable!("the lifetime was inserted above"),
+    };
+    let (parse_impl_generics, _, _) = parse_generics.split_for_impl();
+    let (impl_generics, _, _) = ast.generics.split_for_impl();
+
+    let parse_bounds = add_ty_bounds(&ast.generics, fields.iter().map(|field| &field.ty), |_| {
+        quote! { _struct_deser::ParseBytes<#lifetime> }
+    });
+    let write_bounds = add_ty_bounds(&ast.generics, fields.iter().map(|field| &field.ty), |_| {
+        quote! { _struct_deser::WriteBytes }
+    });
+
+    let mut parse_arms = Tokens::new();
+    let mut len_arms = Tokens::new();
+    let mut write_arms = Tokens::new();
+    for (variant, field) in variants.iter().zip(&fields) {
+        let variant_name = &variant.ident;
+        let ty = &field.ty;
+        let identifier = quote! { <#ty as _struct_deser::Identifier>::IDENTIFIER };
+        let write_id = ser_call(order, quote! { &#identifier }, quote! { id_bytes });
+
+        parse_arms.extend(quote! {
+            #identifier => {
+                let (value, len) = <#ty as _struct_deser::ParseBytes<#lifetime>>::parse(rest)?;
+                _core::result::Result::Ok((#name::#variant_name(value), #id_len + len))
+            }
+        });
+        len_arms.extend(quote! {
+            #name::#variant_name(ref value) => _struct_deser::WriteBytes::serialized_len(value),
+        });
+        write_arms.extend(quote! {
+            #name::#variant_name(ref value) => {
+                #write_id?;
+                _struct_deser::WriteBytes::write_bytes(value, rest)?
+            }
+        });
+    }
+
+    let read_id = deser_call(order, quote! { &bytes[..#id_len] });
+
+    // The type of the identifier isn't known here, so `warn_missing_byte_order` can't be used.
+    // Method resolution picks a deprecated method for numbers instead.
+    let mut res = Tokens::new();
+    if attrs.byte_order.is_none() {
+        let (_, _, where_clause) = ast.generics.split_for_impl();
+        res.extend(quote_spanned! { name.span() =>
+            #[allow(dead_code)]
+            fn __check_identifier_byte_order #impl_generics () #where_clause {
+                _struct_deser::ordered::MissingByteOrder::<#id_ty>(
+                    _struct_deser::ordered::NoByteOrder(_core::marker::PhantomData)
+                ).check();
+            }
+        });
+    }
+
+    // Duplicate identifiers would make some variants unreachable, so they are rejected during
+    // const evaluation. Consts can't use generic parameters, so such variants are not checked.
+    for (i, (variant, field)) in variants.iter().zip(&fields).enumerate() {
+        if depends_on_generics(&field.ty, &ast.generics) {
+            continue;
+        }
+
+        let ty = &field.ty;
+        let mut arms = Tokens::new();
+        for (prev_variant, prev_field) in variants[..i].iter().zip(&fields) {
+            if depends_on_generics(&prev_field.ty, &ast.generics) {
+                continue;
+            }
+
+            let prev_ty = &prev_field.ty;
+            let message = format!(
+                "Variants `{}` and `{}` of `{}` have the same identifier",
+                prev_variant.ident, variant.ident, name
+            );
+            arms.extend(quote! {
+                <#prev_ty as _struct_deser::Identifier>::IDENTIFIER => panic!(#message),
+            });
+        }
+
+        res.extend(quote_spanned! { variant.span() =>
+            const _: () = match <#ty as _struct_deser::Identifier>::IDENTIFIER {
+                #arms
+                _ => (),
+            };
+        });
+    }
+
+    res.extend(quote! {
+        impl #parse_impl_generics _struct_deser::ParseBytes<#lifetime> for #name #ty_generics #parse_bounds {
+            fn parse(bytes: &#lifetime [u8]) -> _core::result::Result<(Self, usize), _struct_deser::Error> {
+                if bytes.len() < #id_len {
+                    return _core::result::Result::Err(_struct_deser::Error::InvalidLength {
+                        expected: #id_len,
+                        actual: bytes.len(),
+                        field: _core::option::Option::None,
+                    });
+                }
+
+                let id: #id_ty = #read_id?;
+                let rest = &bytes[#id_len..];
+                match id {
+                    #parse_arms
+                    _ => _core::result::Result::Err(_struct_deser::Error::UnknownIdentifier {
+                        type_name: stringify!(#name),
+                        field: _core::option::Option::None,
+                    }),
+                }
+            }
+        }
+
+        impl #impl_generics _struct_deser::WriteBytes for #name #ty_generics #write_bounds {
+            fn serialized_len(&self) -> usize {
+                #id_len + match *self {
+                    #len_arms
+                }
+            }
+
+            fn write_bytes(&self, bytes: &mut [u8]) -> _core::result::Result<usize, _struct_deser::Error> {
+                let required = _struct_deser::WriteBytes::serialized_len(self);
+                if bytes.len() < required {
+                    return _core::result::Result::Err(_struct_deser::Error::BufferTooSmall {
+                        required: required,
+                        actual: bytes.len(),
+                    });
+                }
+
+                let (id_bytes, rest) = bytes.split_at_mut(#id_len);
+                let len = match *self {
+                    #write_arms
+                };
+                _core::result::Result::Ok(#id_len + len)
+            }
+        }
+    });
+
+    Ok(wrap_impls(res))
+}
+
+// Wraps generated impls in a block with crates used by generated code
+fn wrap_impls(impls: Tokens) -> Tokens {
+    quote! {
+        const _: () = {
+            extern crate core as _core;
+            extern crate struct_deser as _struct_deser;
+            #impls
+        };
+    }
+}
+
+// Impls identifier trait
+fn impl_identifier(ast: &syn::DeriveInput, attrs: &TypeAttrs, res: &mut Tokens) {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    if let Some((ref val, ref ty)) = attrs.identifier {
+        res.extend(quote! {
+            impl #impl_generics _struct_deser::Identifier for #name #ty_generics #where_clause {
+                type IdentifierType = #ty;
+                const IDENTIFIER: Self::IdentifierType = #val;
+            }
+        });
+    }
+}
+
+// Determines byte order of the field, `#[be]`, `#[le]` and
+// `#[struct_deser(byte_order = "...")]` override the default
+fn get_field_order(field: StructField, mode: Mode, default: Option<ByteOrder>) -> FieldOrder {
+    match field.attrs.byte_order {
+        Some(Some(byte_order)) => FieldOrder::Fixed(byte_order),
+        Some(None) => FieldOrder::None,
+        None => get_default_order(mode, default),
+    }
+}
+
+// Determines byte order of values without byte order attributes
+fn get_default_order(mode: Mode, default: Option<ByteOrder>) -> FieldOrder {
+    match (mode, default) {
+        (Mode::Ordered, _) => FieldOrder::Inherited,
+        (Mode::Plain, Some(byte_order)) => FieldOrder::Fixed(byte_order),
+        (Mode::Plain, None) => FieldOrder::None,
+    }
+}
+
+// Determines type of discriminant on wire
+//
+// `#[struct_deser(repr = "...")]` takes precedence over `#[repr(...)]`.
+fn get_repr(attrs: &[syn::Attribute], type_attrs: &TypeAttrs) -> syn::Result<Option<syn::Type>> {
+    use syn::punctuated::Punctuated;
+    use syn::Meta;
+
+    if let Some(ref repr) = type_attrs.repr {
+        return Ok(Some(repr.clone()));
+    }
+
+    for attr in attrs {
+        if attr.path().is_ident("repr") {
+            let items =
+                attr.parse_args_with(Punctuated::<Meta, syn::Token![,]>::parse_terminated)?;
+            for item in items {
+                if let Meta::Path(ref path) = item {
+                    if !path.is_ident("C") {
+                        return Ok(Some(syn::parse_quote!(#path)));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// Checks whether the variant is marked with `#[struct_deser(other)]`
+fn is_other(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut other = false;
+    for attr in struct_deser_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("other") {
+                other = true;
+                Ok(())
+            } else {
+                Err(unknown_option(&meta, "variant"))
+            }
+        })?;
+    }
+
+    Ok(other)
+}
+
+// Options of a struct or an enum given by `#[struct_deser(...)]` attributes
+#[derive(Default)]
+struct TypeAttrs {
+    // `byte_order = "..."`
+    byte_order: Option<ByteOrder>,
+    // `bit_order = "..."`, defaults to MSB first
+    bit_order: BitOrder,
+    // `ordered`
+    ordered: bool,
+    // `view`
+    view: bool,
+    // `magic = "..."`
+    magic: Option<Vec<u8>>,
+    // `pad = N` along with `fill = B` and `verify_pad`
+    pad: PadAttrs,
+    // `size = N` along with the span of the option
+    size: Option<(usize, Span)>,
+    // `repr = "..."`
+    repr: Option<syn::Type>,
+    // `identifier = "..."` and `identifier_type = "..."`
+    identifier: Option<(syn::Expr, syn::Type)>,
+}
+
+impl TypeAttrs {
+    // Parses and validates the options, rejecting unknown ones
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut res = TypeAttrs::default();
+        let mut bit_order = None;
+        let mut identifier = None;
+        let mut identifier_type = None;
+
+        for attr in struct_deser_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("byte_order") {
+                    check_unique(&meta, res.byte_order.is_some())?;
+                    res.byte_order = match parse_byte_order(&meta)? {
+                        Some(byte_order) => Some(byte_order),
+                        None => {
+                            return Err(meta.error(
+                                "Default byte order must be one of \"be\", \"le\" or \"ne\"",
+                            ))
+                        }
+                    };
+                } else if meta.path.is_ident("bit_order") {
+                    check_unique(&meta, bit_order.is_some())?;
+                    let message = "Bit order must be either \"msb\" or \"lsb\"";
+                    let value = parse_str(&meta, message)?;
+                    bit_order = match value.value().as_str() {
+                        "msb" => Some(BitOrder::Msb),
+                        "lsb" => Some(BitOrder::Lsb),
+                        _ => return Err(syn::Error::new_spanned(value, message)),
+                    };
+                } else if meta.path.is_ident("ordered") {
+                    res.ordered = true;
+                } else if meta.path.is_ident("view") {
+                    res.view = true;
+                } else if meta.path.is_ident("magic") {
+                    check_unique(&meta, res.magic.is_some())?;
+                    res.magic = Some(parse_magic(&meta)?);
+                } else if meta.path.is_ident("size") {
+                    check_unique(&meta, res.size.is_some())?;
+                    let span = meta.path.span();
+                    res.size = Some((parse_int(&meta, "Size must be an integer")?, span));
+                } else if meta.path.is_ident("repr") {
+                    check_unique(&meta, res.repr.is_some())?;
+                    let value = parse_str(&meta, "Representation must be a type inside string")?;
+                    res.repr = Some(value.parse()?);
+                } else if meta.path.is_ident("identifier") {
+                    check_unique(&meta, identifier.is_some())?;
+                    let value = parse_str(&meta, "Identifier must be an expression inside string")?;
+                    identifier = Some((value.parse::<syn::Expr>()?, meta.path.clone()));
+                } else if meta.path.is_ident("identifier_type") {
+                    check_unique(&meta, identifier_type.is_some())?;
+                    let value = parse_str(&meta, "Type of identifier must be inside string")?;
+                    identifier_type = Some((value.parse::<syn::Type>()?, meta.path.clone()));
+                } else if !res.pad.parse(&meta)? {
+                    return Err(unknown_option(&meta, "type"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        res.bit_order = bit_order.unwrap_or_default();
+        res.identifier = match (identifier, identifier_type) {
+            (Some((val, _)), Some((ty, _))) => Some((val, ty)),
+            (None, None) => None,
+            (Some((_, path)), None) | (None, Some((_, path))) => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "Both identifier and type must be specified or none of them",
+                ))
+            }
+        };
+        res.pad.check()?;
+
+        Ok(res)
+    }
+}
+
+// Options of a field given by its attributes
+#[derive(Default)]
+struct FieldAttrs {
+    // `#[be]`, `#[le]`, `#[ne]` or `byte_order = "..."`
+    //
+    // `Some(None)` means explicit "none" - the type doesn't depend on byte order.
+    byte_order: Option<Option<ByteOrder>>,
+    // `#[bits = N]`
+    bits: Option<u32>,
+    // `len_from = "..."`
+    len_from: Option<syn::LitStr>,
+    // `magic = "..."` or `const = "..."`
+    constant: Option<Constant>,
+    // `pad = N` along with `fill = B` and `verify_pad`
+    pad: PadAttrs,
+    // `offset = N`
+    offset: Option<usize>,
+}
+
+impl FieldAttrs {
+    // Parses and validates the options, rejecting unknown ones
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut res = FieldAttrs::default();
+        let mut bits_attr = None;
+        let mut constant_path = None;
+
+        for attr in attrs {
+            let byte_order = if attr.path().is_ident("be") {
+                ByteOrder::BE
+            } else if attr.path().is_ident("le") {
+                ByteOrder::LE
+            } else if attr.path().is_ident("ne") {
+                ByteOrder::NE
+            } else if attr.path().is_ident("bits") {
+                if res.bits.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &attr.meta,
+                        "Bit width specified multiple times",
+                    ));
+                }
+
+                let value = &attr.meta.require_name_value()?.value;
+                let width = match *value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(ref width),
+                        ..
+                    }) => width.base10_parse::<u32>().ok(),
+                    _ => None,
+                };
+                res.bits = match width {
+                    Some(width) if width > 0 && width <= 128 => Some(width),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "Bit width must be an integer between 1 and 128",
+                        ))
+                    }
+                };
+                bits_attr = Some(attr);
+                continue;
+            } else {
+                continue;
+            };
+
+            attr.meta.require_path_only()?;
+            if res.byte_order.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &attr.meta,
+                    "Conflicting byte order: byte order can be specified only once",
+                ));
+            }
+            res.byte_order = Some(Some(byte_order));
+        }
+
+        for attr in struct_deser_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("byte_order") {
+                    if res.byte_order.is_some() {
+                        return Err(meta.error(
+                            "Conflicting byte order: byte order can be specified only once",
+                        ));
+                    }
+                    res.byte_order = Some(parse_byte_order(&meta)?);
+                } else if meta.path.is_ident("len_from") {
+                    check_unique(&meta, res.len_from.is_some())?;
+                    let message = "Name of length field must be inside string";
+                    res.len_from = Some(parse_str(&meta, message)?);
+                } else if meta.path.is_ident("magic") {
+                    check_unique(&meta, res.constant.is_some())?;
+                    res.constant = Some(Constant::Magic(parse_magic(&meta)?));
+                    constant_path = Some(meta.path.clone());
+                } else if meta.path.is_ident("const") || meta.path.is_ident("constant") {
+                    check_unique(&meta, res.constant.is_some())?;
+                    let value = parse_str(&meta, "Constant must be an expression inside string")?;
+                    res.constant = Some(Constant::Value(value.parse()?));
+                    constant_path = Some(meta.path.clone());
+                } else if meta.path.is_ident("offset") {
+                    check_unique(&meta, res.offset.is_some())?;
+                    res.offset = Some(parse_int(&meta, "Offset must be an integer")?);
+                } else if !res.pad.parse(&meta)? {
+                    return Err(unknown_option(&meta, "field"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        if let (Some(attr), Some(_)) = (bits_attr, res.byte_order) {
+            return Err(syn::Error::new_spanned(
+                &attr.meta,
+                "Bit field can't have byte order",
+            ));
+        }
+
+        if let (Some(_), Some(path)) = (bits_attr, constant_path) {
+            return Err(syn::Error::new_spanned(
+                path,
+                "Bit field can't be magic or constant",
+            ));
+        }
+
+        match (res.pad.len, res.offset, &res.pad.path) {
+            (Some(_), Some(_), Some(path)) => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "Padding and offset can't be specified together",
+                ))
+            }
+            // Options of padding apply to the gap before the offset
+            (None, Some(_), _) => (),
+            _ => res.pad.check()?,
+        }
+
+        Ok(res)
+    }
+}
+
+// Options of padding shared by fields and types
+#[derive(Default)]
+struct PadAttrs {
+    // `pad = N` or `skip_bytes = N`
+    len: Option<usize>,
+    // `fill = B`
+    fill: Option<u8>,
+    // `verify_pad`
+    verify: bool,
+    // The first of the options, errors point at it
+    path: Option<syn::Path>,
+}
+
+impl PadAttrs {
+    // Parses the option if it's related to padding, returns `false` otherwise
+    fn parse(&mut self, meta: &syn::meta::ParseNestedMeta) -> syn::Result<bool> {
+        if meta.path.is_ident("pad") || meta.path.is_ident("skip_bytes") {
+            if self.len.is_some() {
+                return Err(meta.error("Padding specified multiple times"));
+            }
+
+            let message = "Length of padding must be a positive integer";
+            match parse_int(meta, message)? {
+                0 => return Err(meta.error(message)),
+                len => self.len = Some(len),
+            }
+        } else if meta.path.is_ident("fill") {
+            check_unique(meta, self.fill.is_some())?;
+            self.fill = Some(parse_int(meta, "Fill of padding must be a byte")?);
+        } else if meta.path.is_ident("verify_pad") {
+            self.verify = true;
+        } else {
+            return Ok(false);
+        }
+
+        if self.path.is_none() {
+            self.path = Some(meta.path.clone());
+        }
+        Ok(true)
+    }
+
+    // Rejects `fill` and `verify_pad` without padding
+    fn check(&self) -> syn::Result<()> {
+        match self.path {
+            Some(ref path) if self.len.is_none() => Err(syn::Error::new_spanned(
+                path,
+                "Padding options require `pad = N` or `offset = N`",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Creates padding of `#[struct_deser(pad = N)]`
+//
+// Fields with `#[struct_deser(offset = N)]` are preceded by padding filling the gap from `start`
+// instead.
+fn get_pad(pad: &PadAttrs, offset: Option<usize>, start: Option<&Tokens>) -> Option<Item<'static>> {
+    let len = match (pad.len, offset, start) {
+        (Some(len), _, _) => quote! { #len },
+        (None, Some(offset), Some(start)) => quote! { #offset - (#start) },
+        _ => return None,
+    };
+
+    Some(Item::Pad {
+        len,
+        fill: pad.fill.unwrap_or(0),
+        verify: pad.verify,
+    })
+}
+
+// Value of a field which is always the same
+enum Constant {
+    // Serialized bytes of the field
+    Magic(Vec<u8>),
+    // Expression evaluating to the value of the field
+    Value(syn::Expr),
+}
+
+// Returns all `#[struct_deser(...)]` attributes
+fn struct_deser_attrs(attrs: &[syn::Attribute]) -> impl Iterator<Item = &syn::Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("struct_deser"))
+}
+
+// Creates error about option which isn't supported on the item
+fn unknown_option(meta: &syn::meta::ParseNestedMeta, item: &str) -> syn::Error {
+    let path = &meta.path;
+    let name = quote!(#path).to_string().replace(' ', "");
+    meta.error(format!("Unknown {} option `{}`", item, name))
+}
+
+// Rejects option which was already specified
+fn check_unique(meta: &syn::meta::ParseNestedMeta, specified: bool) -> syn::Result<()> {
+    if specified {
+        let path = &meta.path;
+        let name = quote!(#path).to_string().replace(' ', "");
+        Err(meta.error(format!("`{}` specified multiple times", name)))
+    } else {
+        Ok(())
+    }
+}
+
+// Parses string value of the option
+fn parse_str(meta: &syn::meta::ParseNestedMeta, message: &str) -> syn::Result<syn::LitStr> {
+    match meta.value()?.parse::<syn::Lit>()? {
+        syn::Lit::Str(value) => Ok(value),
+        lit => Err(syn::Error::new_spanned(lit, message)),
+    }
+}
+
+// Parses integer value of the option
+fn parse_int<N>(meta: &syn::meta::ParseNestedMeta, message: &str) -> syn::Result<N>
+where
+    N: std::str::FromStr,
+    N::Err: std::fmt::Display,
+{
+    match meta.value()?.parse::<syn::Lit>()? {
+        syn::Lit::Int(ref value) => value
+            .base10_parse()
+            .map_err(|_| syn::Error::new_spanned(value, message)),
+        lit => Err(syn::Error::new_spanned(lit, message)),
+    }
+}
+
+// Parses byte order, `None` means explicit "none" - the type doesn't depend on byte order
+fn parse_byte_order(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Option<ByteOrder>> {
+    let message = "Byte order must be one of \"be\", \"le\", \"ne\" or \"none\"";
+    let value = parse_str(meta, message)?;
+    match value.value().as_str() {
+        "be" => Ok(Some(ByteOrder::BE)),
+        "le" => Ok(Some(ByteOrder::LE)),
+        "ne" => Ok(Some(ByteOrder::NE)),
+        "none" => Ok(None),
+        _ => Err(syn::Error::new_spanned(value, message)),
+    }
+}
+
+// Parses string or byte string value of the option as magic bytes
+fn parse_magic(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Vec<u8>> {
+    use syn::Lit;
+
+    let lit = meta.value()?.parse::<Lit>()?;
+    let magic = match lit {
+        Lit::Str(ref value) => value.value().into_bytes(),
+        Lit::ByteStr(ref value) => value.value(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                lit,
+                "Magic must be a string or byte string",
+            ))
+        }
+    };
+
+    if magic.is_empty() {
+        return Err(syn::Error::new_spanned(lit, "Magic can't be empty"));
+    }
+
+    Ok(magic)
+}
+
+// Creates byte string literal
+fn byte_str(bytes: &[u8]) -> syn::LitByteStr {
+    syn::LitByteStr::new(bytes, Span::call_site())
+}
+
+// Returns the where clause of the generics, which is empty if there's none
+fn where_clause(generics: &syn::Generics) -> syn::WhereClause {
+    match generics.where_clause {
+        Some(ref where_clause) => where_clause.clone(),
+        None => syn::WhereClause {
+            where_token: Default::default(),
+            predicates: Default::default(),
+        },
+    }
+}
+
+// Creates where clause of an impl by adding `bound` to the type of each field which depends on
+// type parameters
+//
+// Bit fields are always bound by `BitField`.
+fn add_bounds<'a, 'b: 'a, I, F>(generics: &syn::Generics, layout: I, bound: F) -> syn::WhereClause
+where
+    I: IntoIterator<Item = &'a Item<'b>>,
+    F: Fn(StructField<'b>) -> Tokens,
+{
+    let mut where_clause = where_clause(generics);
+    for item in layout {
+        match *item {
+            Item::Field(field) => {
+                add_bound(&mut where_clause, generics, &field.field.ty, bound(field))
+            }
+            Item::Bits { ref fields, .. } => {
+                for &(field, _) in fields {
+                    let bound = quote! { _struct_deser::BitField };
+                    add_bound(&mut where_clause, generics, &field.field.ty, bound);
+                }
+            }
+            Item::Magic(_) | Item::Pad { .. } => (),
+        }
+    }
+
+    where_clause
+}
+
+// Adds `bound` to the type of each field which doesn't depend on type parameters
+//
+// The predicates are higher-ranked, otherwise unsatisfied ones would fail to compile instead of
+// making the impl unavailable.
+fn add_concrete_bounds<'a, 'b: 'a, I, F>(
+    where_clause: &mut syn::WhereClause,
+    generics: &syn::Generics,
+    layout: I,
+    bound: F,
+) where
+    I: IntoIterator<Item = &'a Item<'b>>,
+    F: Fn(StructField<'b>) -> Tokens,
+{
+    for item in layout {
+        if let Item::Field(field) = *item {
+            let ty = &field.field.ty;
+            if !depends_on_ty_params(ty, generics) {
+                let bound = bound(field);
+                where_clause
+                    .predicates
+                    .push(syn::parse_quote! { for<'__struct_deser> #ty: #bound });
+            }
+        }
+    }
+}
+
+// Creates where clause of an impl by adding `bound` to each of the types which depend on type
+// parameters
+fn add_ty_bounds<'a, I, F>(generics: &syn::Generics, types: I, bound: F) -> syn::WhereClause
+where
+    I: IntoIterator<Item = &'a syn::Type>,
+    F: Fn(&syn::Type) -> Tokens,
+{
+    let mut where_clause = where_clause(generics);
+    for ty in types {
+        add_bound(&mut where_clause, generics, ty, bound(ty));
+    }
+
+    where_clause
+}
+
+// Adds `ty: bound` predicate to the where clause if the type depends on type parameters
+fn add_bound(
+    where_clause: &mut syn::WhereClause,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+    bound: Tokens,
+) {
+    if depends_on_ty_params(ty, generics) {
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { #ty: #bound });
+    }
+}
+
+// Checks whether the type mentions any of the type or lifetime parameters
+fn depends_on_generics(ty: &syn::Type, generics: &syn::Generics) -> bool {
+    let ty_str = quote!(#ty).to_string();
+    depends_on_ty_params(ty, generics)
+        || ty_str
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '\''))
+            .any(|word| {
+                generics
+                    .lifetimes()
+                    .any(|def| def.lifetime.to_string() == word)
+            })
+}
+
+// Checks whether the type mentions any of the type parameters
+fn depends_on_ty_params(ty: &syn::Type, generics: &syn::Generics) -> bool {
+    let ty = quote!(#ty).to_string();
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|word| generics.type_params().any(|param| param.ident == word))
 }
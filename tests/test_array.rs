@@ -0,0 +1,58 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, FromBytes, IntoBytes, SerializedByteLen, TryFromBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Arrays {
+    big: [u8; 300],
+    #[be]
+    samples: [u16; 3],
+    #[le]
+    words: [[u32; 2]; 2],
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Pair(u8, #[be] u16);
+
+#[test]
+fn byte_len() {
+    assert_eq!(<[u8; 300]>::BYTE_LEN, 300);
+    assert_eq!(<[u16; 8]>::BYTE_LEN, 16);
+    assert_eq!(<[Pair; 4]>::BYTE_LEN, 12);
+    assert_eq!(Arrays::BYTE_LEN, 300 + 6 + 16);
+}
+
+#[test]
+fn roundtrip() {
+    let arrays = Arrays {
+        big: [42; 300],
+        samples: [1, 2, 3],
+        words: [[4, 5], [6, 7]],
+    };
+
+    let mut bytes = [0; Arrays::BYTE_LEN];
+    arrays.into_bytes(&mut bytes);
+    assert_eq!(&bytes[300..306], &[0, 1, 0, 2, 0, 3]);
+    assert_eq!(&bytes[306..314], &[4, 0, 0, 0, 5, 0, 0, 0]);
+    assert_eq!(Arrays::from_bytes(&bytes), arrays);
+}
+
+#[test]
+fn nested_structs() {
+    let pairs = [Pair(1, 2), Pair(3, 4)];
+    let mut bytes = [0; 6];
+    pairs.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [1, 0, 2, 3, 0, 4]);
+    assert_eq!(<[Pair; 2]>::try_from_bytes(&bytes), Ok(pairs));
+    assert_eq!(
+        <[Pair; 2]>::try_from_bytes(&bytes[..5]),
+        Err(Error::InvalidLength {
+            expected: 6,
+            actual: 5,
+            field: None,
+        })
+    );
+}
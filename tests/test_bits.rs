@@ -0,0 +1,93 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{BitField, Error, FromBytes, IntoBytes, SerializedByteLen, TryIntoBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct TcpFlags {
+    #[be]
+    port: u16,
+    #[bits = 4]
+    data_offset: u8,
+    #[bits = 3]
+    reserved: u8,
+    #[bits = 1]
+    ns: bool,
+    #[bits = 8]
+    flags: u8,
+    ttl: u8,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(bit_order = "lsb")]
+struct Register(#[bits = 1] bool, #[bits = 3] u8, #[bits = 12] u16);
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Generic<T: BitField> {
+    #[bits = 4]
+    low: T,
+    #[bits = 4]
+    high: u8,
+}
+
+#[test]
+fn msb_first() {
+    assert_eq!(TcpFlags::BYTE_LEN, 2 + 2 + 1);
+
+    let flags = TcpFlags {
+        port: 443,
+        data_offset: 5,
+        reserved: 0,
+        ns: true,
+        flags: 0x12,
+        ttl: 64,
+    };
+    let mut bytes = [0; TcpFlags::BYTE_LEN];
+    flags.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [1, 187, 0x51, 0x12, 64]);
+    assert_eq!(TcpFlags::from_bytes(&bytes), flags);
+}
+
+#[test]
+fn lsb_first() {
+    assert_eq!(Register::BYTE_LEN, 2);
+
+    let register = Register(true, 2, 0xabc);
+    let mut bytes = [0; Register::BYTE_LEN];
+    register.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0xc5, 0xab]);
+    assert_eq!(Register::from_bytes(&bytes), register);
+}
+
+#[test]
+fn generic() {
+    let value = Generic { low: 3u16, high: 9 };
+    let mut bytes = [0; 1];
+    value.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0x39]);
+    assert_eq!(Generic::<u16>::from_bytes(&bytes), value);
+}
+
+#[test]
+fn out_of_range() {
+    let flags = TcpFlags {
+        port: 443,
+        data_offset: 16,
+        reserved: 0,
+        ns: false,
+        flags: 0,
+        ttl: 64,
+    };
+    let mut bytes = [0; TcpFlags::BYTE_LEN];
+
+    assert_eq!(
+        flags.try_into_bytes(&mut bytes),
+        Err(Error::OutOfRange {
+            field: Some("data_offset")
+        })
+    );
+}
@@ -0,0 +1,78 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::byteorder::{U16Be, U32Le};
+use struct_deser::{SerializedByteLen, ToByteArray};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Packet {
+    #[be]
+    version: u16,
+    ttl: u8,
+    #[le]
+    chksum: u32,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(ordered, byte_order = "le")]
+struct Ordered {
+    id: u16,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq, Copy, Clone)]
+#[struct_deser(repr = "u16", byte_order = "be")]
+enum Opcode {
+    Read = 1,
+    Write = 2,
+}
+
+const MAGIC: [u8; 4] = U32Le::from_byte_array([0xD4, 0xC3, 0xB2, 0xA1]).to_byte_array();
+const LEN: U16Be = U16Be::from_byte_array([1, 2]);
+
+fn round_trip<T: ToByteArray>(value: &T) -> T {
+    T::from_byte_array(&value.to_byte_array())
+}
+
+#[test]
+fn structs() {
+    let packet = Packet {
+        version: 1,
+        ttl: 42,
+        chksum: 47,
+    };
+
+    let bytes = packet.to_byte_array();
+    assert_eq!(bytes.len(), Packet::BYTE_LEN);
+    assert_eq!(bytes, [0, 1, 42, 47, 0, 0, 0]);
+    assert_eq!(Packet::from_byte_array(&bytes), packet);
+
+    let ordered = Ordered { id: 0x0102 };
+    assert_eq!(ordered.to_byte_array(), [2, 1]);
+    assert_eq!(round_trip(&ordered), ordered);
+}
+
+#[test]
+fn enums() {
+    assert_eq!(Opcode::Write.to_byte_array(), [0, 2]);
+    assert_eq!(Opcode::from_byte_array(&[0, 1]), Opcode::Read);
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(42u8.to_byte_array(), [42]);
+    assert_eq!(i8::from_byte_array(&[0xff]), -1);
+    assert_eq!([1u8, 2, 3].to_byte_array(), [1, 2, 3]);
+    assert_eq!(U16Be::new(0x0102).to_byte_array(), [1, 2]);
+    assert_eq!(
+        <U32Le as ToByteArray>::from_byte_array(&[1, 0, 0, 0]).get(),
+        1
+    );
+}
+
+#[test]
+fn const_fn() {
+    assert_eq!(MAGIC, [0xD4, 0xC3, 0xB2, 0xA1]);
+    assert_eq!(LEN.get(), 0x0102);
+    assert_eq!(U32Le::from_byte_array(MAGIC).get(), 0xA1B2C3D4);
+}
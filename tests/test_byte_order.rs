@@ -36,4 +36,39 @@ mod test_primitive {
     test_primitive!(i16, I16, 42, [42, 0, 0, 42]);
     test_primitive!(i32, I32, 42, [42, 0, 0, 0, 0, 0, 0, 42]);
     test_primitive!(i64, I64, 42, [42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 42]);
+    test_primitive!(u128, U128, 42, [42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 42]);
+    test_primitive!(i128, I128, 42, [42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 42]);
+}
+
+#[derive(StructDeser, Debug, PartialEq)]
+struct Floats {
+    #[le]
+    f32_le: f32,
+    #[be]
+    f32_be: f32,
+    #[le]
+    f64_le: f64,
+    #[be]
+    f64_be: f64,
+}
+
+#[test]
+fn floats() {
+    use struct_deser::{FromBytes, IntoBytes, SerializedByteLen};
+
+    let floats = Floats {
+        f32_le: 1.0,
+        f32_be: 1.0,
+        f64_le: -2.0,
+        f64_be: -2.0,
+    };
+    let mut bytes = [0; Floats::BYTE_LEN];
+    floats.into_bytes(&mut bytes);
+
+    assert_eq!(&bytes[..8], &[0, 0, 0x80, 0x3f, 0x3f, 0x80, 0, 0]);
+    assert_eq!(
+        &bytes[8..],
+        &[0, 0, 0, 0, 0, 0, 0, 0xc0, 0xc0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(Floats::from_bytes(&bytes), floats);
 }
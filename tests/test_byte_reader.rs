@@ -0,0 +1,106 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::byteorder::{BE, LE};
+use struct_deser::{ByteReader, ByteWriter, Error};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be")]
+struct Header {
+    id: u16,
+    flags: u8,
+}
+
+#[test]
+fn read() {
+    let bytes = [0, 42, 3, 1, 2, 0xff, 4, 0, 0, 0];
+    let mut reader = ByteReader::new(&bytes);
+
+    assert_eq!(reader.read::<Header>(), Header { id: 42, flags: 3 });
+    assert_eq!(reader.read_ordered::<u16, BE>(), 0x0102);
+    assert_eq!(reader.read::<i8>(), -1);
+    assert_eq!(reader.position(), 6);
+    assert_eq!(reader.remaining(), 4);
+    assert_eq!(reader.rest(), &[4, 0, 0, 0]);
+    assert_eq!(reader.read_ordered::<u32, LE>(), 4);
+    assert_eq!(reader.remaining(), 0);
+}
+
+#[test]
+fn skip() {
+    let bytes = [1, 2, 3, 4];
+    let mut reader = ByteReader::new(&bytes);
+
+    reader.skip(1);
+    assert_eq!(reader.read_bytes(2), &[2, 3]);
+    reader.skip(1);
+    assert_eq!(reader.remaining(), 0);
+}
+
+#[test]
+fn try_read() {
+    let bytes = [0, 42, 3, 1];
+    let mut reader = ByteReader::new(&bytes);
+
+    assert_eq!(reader.try_read::<Header>(), Ok(Header { id: 42, flags: 3 }));
+    assert_eq!(
+        reader.try_read_ordered::<u16, BE>(),
+        Err(Error::InvalidLength {
+            expected: 5,
+            actual: 4,
+            field: None,
+        })
+    );
+    // failed reads don't advance
+    assert_eq!(reader.position(), 3);
+    assert!(reader.try_skip(2).is_err());
+    assert_eq!(reader.try_read::<u8>(), Ok(1));
+}
+
+#[test]
+#[should_panic]
+fn read_too_short() {
+    ByteReader::new(&[1]).read_ordered::<u16, BE>();
+}
+
+#[test]
+fn write() {
+    let mut bytes = [0xaa; 10];
+    let mut writer = ByteWriter::new(&mut bytes);
+
+    writer.write(&Header { id: 42, flags: 3 });
+    writer.write_ordered::<u16, LE>(&0x0102);
+    writer.skip(1);
+    writer.fill(2, 0);
+    writer.write_bytes(&[7]);
+    assert_eq!(writer.position(), 9);
+    assert_eq!(writer.remaining(), 1);
+
+    assert_eq!(bytes, [0, 42, 3, 2, 1, 0xaa, 0, 0, 7, 0xaa]);
+}
+
+#[test]
+fn try_write() {
+    let mut bytes = [0; 4];
+    let mut writer = ByteWriter::new(&mut bytes);
+
+    assert_eq!(writer.try_write(&Header { id: 1, flags: 2 }), Ok(()));
+    assert_eq!(
+        writer.try_write_ordered::<u16, BE>(&3),
+        Err(Error::BufferTooSmall {
+            required: 5,
+            actual: 4,
+        })
+    );
+    assert!(writer.try_fill(2, 0).is_err());
+    assert_eq!(writer.try_write_bytes(&[4]), Ok(()));
+    assert_eq!(writer.remaining(), 0);
+    assert_eq!(bytes, [0, 1, 2, 4]);
+}
+
+#[test]
+#[should_panic]
+fn write_too_short() {
+    ByteWriter::new(&mut [0; 1]).write_ordered::<u16, BE>(&1);
+}
@@ -0,0 +1,108 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, IntoBytes, SerializedByteLen, TryFromBytes, TryIntoBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(magic = b"\x89PNG")]
+struct PngStart {
+    #[be]
+    len: u32,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "le")]
+struct PcapHeader {
+    #[struct_deser(constant = "0xa1b2c3d4")]
+    magic: u32,
+    #[struct_deser(constant = "0")]
+    reserved: u16,
+    version: u16,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(view)]
+struct Tagged {
+    #[struct_deser(magic = "ID")]
+    tag: [u8; 2],
+    value: u8,
+}
+
+#[test]
+fn struct_magic() {
+    assert_eq!(PngStart::BYTE_LEN, 8);
+
+    let mut bytes = [0; PngStart::BYTE_LEN];
+    PngStart { len: 13 }.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0x89, b'P', b'N', b'G', 0, 0, 0, 13]);
+    assert_eq!(PngStart::try_from_bytes(&bytes), Ok(PngStart { len: 13 }));
+
+    bytes[1] = b'Q';
+    assert_eq!(
+        PngStart::try_from_bytes(&bytes),
+        Err(Error::InvalidConstant {
+            type_name: "PngStart",
+            field: None,
+        })
+    );
+}
+
+#[test]
+fn constant_written_regardless_of_value() {
+    let header = PcapHeader {
+        magic: 0,
+        reserved: 42,
+        version: 2,
+    };
+    let mut bytes = [0; PcapHeader::BYTE_LEN];
+
+    assert_eq!(header.try_into_bytes(&mut bytes), Ok(8));
+    assert_eq!(bytes, [0xd4, 0xc3, 0xb2, 0xa1, 0, 0, 2, 0]);
+    assert_eq!(
+        PcapHeader::try_from_bytes(&bytes),
+        Ok(PcapHeader {
+            magic: 0xa1b2c3d4,
+            reserved: 0,
+            version: 2,
+        })
+    );
+}
+
+#[test]
+fn invalid_constant() {
+    let bytes = [0xd4, 0xc3, 0xb2, 0xa1, 1, 0, 2, 0];
+
+    assert_eq!(
+        PcapHeader::try_from_bytes(&bytes),
+        Err(Error::InvalidConstant {
+            type_name: "PcapHeader",
+            field: Some("reserved"),
+        })
+    );
+    assert_eq!(
+        PcapHeader::try_from_bytes(&bytes).unwrap_err().to_string(),
+        "invalid value of constant field `reserved` of PcapHeader"
+    );
+}
+
+#[test]
+fn field_magic() {
+    let mut bytes = [0; Tagged::BYTE_LEN];
+    Tagged {
+        tag: *b"XX",
+        value: 7,
+    }
+    .into_bytes(&mut bytes);
+
+    assert_eq!(bytes, *b"ID\x07");
+    assert_eq!(Tagged::view(&bytes).tag(), *b"ID");
+    assert_eq!(
+        Tagged::try_from_bytes(b"IE\x07"),
+        Err(Error::InvalidConstant {
+            type_name: "Tagged",
+            field: Some("tag"),
+        })
+    );
+}
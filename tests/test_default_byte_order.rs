@@ -0,0 +1,109 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, FromBytes, IntoBytes, SerializedByteLen, TryFromBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Inner {
+    #[le]
+    value: u16,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be")]
+struct Header {
+    version: u16,
+    ttl: u8,
+    #[le]
+    chksum: u32,
+    mac: [u8; 3],
+    samples: [u16; 2],
+    #[struct_deser(byte_order = "none")]
+    inner: Inner,
+}
+
+#[derive(StructDeser, Debug, Copy, Clone, Eq, PartialEq)]
+#[struct_deser(repr = "u8")]
+enum Kind {
+    Data = 1,
+    Ack = 2,
+}
+
+// Types which don't depend on byte order don't need `byte_order = "none"`
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be")]
+struct Nested {
+    len: u16,
+    inner: Inner,
+    kind: Kind,
+    inners: [Inner; 2],
+    pairs: [[u8; 2]; 2],
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "le")]
+struct Tuple(u32, #[be] u16);
+
+#[test]
+fn default_byte_order() {
+    let header = Header {
+        version: 1,
+        ttl: 42,
+        chksum: 47,
+        mac: [1, 2, 3],
+        samples: [4, 5],
+        inner: Inner { value: 6 },
+    };
+    let mut bytes = [0; Header::BYTE_LEN];
+    header.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0, 1, 42, 47, 0, 0, 0, 1, 2, 3, 0, 4, 0, 5, 6, 0]);
+    assert_eq!(Header::from_bytes(&bytes), header);
+}
+
+#[test]
+fn nested() {
+    let nested = Nested {
+        len: 1,
+        inner: Inner { value: 2 },
+        kind: Kind::Ack,
+        inners: [Inner { value: 3 }, Inner { value: 4 }],
+        pairs: [[5, 6], [7, 8]],
+    };
+    let mut bytes = [0; Nested::BYTE_LEN];
+    nested.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0, 1, 2, 0, 2, 3, 0, 4, 0, 5, 6, 7, 8]);
+    assert_eq!(Nested::from_bytes(&bytes), nested);
+    assert_eq!(
+        Nested::try_from_bytes(&[0, 1, 2, 0, 9, 3, 0, 4, 0, 5, 6, 7, 8]),
+        Err(Error::UnknownDiscriminant {
+            type_name: "Kind",
+            field: Some("kind"),
+        })
+    );
+}
+
+#[test]
+fn tuple_struct() {
+    let tuple = Tuple(1, 2);
+    let mut bytes = [0; Tuple::BYTE_LEN];
+    tuple.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [1, 0, 0, 0, 0, 2]);
+    assert_eq!(Tuple::from_bytes(&bytes), tuple);
+}
+
+// Bytes don't implement the ordered traits, so importing both families keeps calls unambiguous
+#[test]
+fn byte_method_resolution() {
+    use struct_deser::FromBytesOrdered;
+
+    assert_eq!(u8::from_bytes(&[42]), 42);
+    assert_eq!(<[u8; 2]>::from_bytes(&[1, 2]), [1, 2]);
+    assert_eq!(
+        u16::from_bytes::<struct_deser::byteorder::BE>(&[1, 2]),
+        0x0102
+    );
+}
@@ -0,0 +1,122 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::byteorder::{F32Le, I16Be, U16Be, U32Le, U64Be};
+use struct_deser::{Error, FromBytes, IntoBytes, SerializedByteLen, TryFromBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Header {
+    magic: U32Le,
+    len: U16Be,
+    offset: I16Be,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be")]
+struct Mixed {
+    len: u16,
+    chksum: U32Le,
+}
+
+#[derive(StructDeser, Debug, PartialEq)]
+#[struct_deser(byte_order = "le")]
+struct Samples {
+    count: u16,
+    ports: [U16Be; 2],
+    levels: [F32Le; 2],
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(ordered)]
+struct Ordered {
+    len: u16,
+    chksum: U32Le,
+}
+
+fn decode<T: FromBytes>(bytes: &[u8]) -> T {
+    T::from_bytes(bytes)
+}
+
+#[test]
+fn conversions() {
+    let value = U16Be::new(0x0102);
+
+    assert_eq!(value.get(), 0x0102);
+    assert_eq!(u16::from(value), 0x0102);
+    assert_eq!(U16Be::from(0x0102), value);
+    assert_eq!(format!("{:?}", value), "U16Be(258)");
+    assert_eq!(format!("{:?}", F32Le::new(1.5)), "F32Le(1.5)");
+}
+
+#[test]
+fn comparisons() {
+    assert!(U16Be::new(0x00ff) < U16Be::new(0x0100));
+    assert!(I16Be::new(-1) < I16Be::new(0));
+    assert!(F32Le::new(-0.5) < F32Le::new(0.25));
+    assert_eq!(F32Le::new(0.0), F32Le::new(-0.0));
+    assert_eq!(U64Be::new(42).max(U64Be::new(47)), U64Be::new(47));
+}
+
+#[test]
+fn bytes() {
+    let mut bytes = [0; 4];
+    U32Le::new(0x01020304).into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [4, 3, 2, 1]);
+    assert_eq!(decode::<U32Le>(&bytes).get(), 0x01020304);
+    assert_eq!(
+        U32Le::try_from_bytes(&bytes[..3]),
+        Err(Error::InvalidLength {
+            expected: 4,
+            actual: 3,
+            field: None,
+        })
+    );
+}
+
+#[test]
+fn derive_without_attributes() {
+    let header = Header {
+        magic: U32Le::new(0xa1b2c3d4),
+        len: U16Be::new(20),
+        offset: I16Be::new(-2),
+    };
+    let mut bytes = [0; Header::BYTE_LEN];
+    header.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0xd4, 0xc3, 0xb2, 0xa1, 0, 20, 0xff, 0xfe]);
+    assert_eq!(Header::from_bytes(&bytes), header);
+}
+
+#[test]
+fn default_byte_order() {
+    let mixed = Mixed {
+        len: 0x0102,
+        chksum: U32Le::new(0x03040506),
+    };
+    let mut bytes = [0; Mixed::BYTE_LEN];
+    mixed.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [1, 2, 6, 5, 4, 3]);
+    assert_eq!(Mixed::from_bytes(&bytes), mixed);
+
+    let ordered: Ordered =
+        struct_deser::FromBytesOrdered::from_bytes::<struct_deser::byteorder::LE>(&bytes);
+    assert_eq!(ordered.len, 0x0201);
+    assert_eq!(ordered.chksum.get(), 0x03040506);
+}
+
+#[test]
+fn arrays_under_default_byte_order() {
+    let samples = Samples {
+        count: 2,
+        ports: [U16Be::new(0x0102), U16Be::new(0x0304)],
+        levels: [F32Le::new(1.0), F32Le::new(-2.0)],
+    };
+    let mut bytes = [0; Samples::BYTE_LEN];
+    samples.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [2, 0, 1, 2, 3, 4, 0, 0, 0x80, 0x3f, 0, 0, 0, 0xc0]);
+    assert_eq!(Samples::from_bytes(&bytes), samples);
+}
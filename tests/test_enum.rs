@@ -0,0 +1,108 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, FromBytes, IntoBytes, SerializedByteLen, TryFromBytes};
+
+#[derive(StructDeser, Debug, Copy, Clone, Eq, PartialEq)]
+#[struct_deser(repr = "u16", byte_order = "be")]
+enum Opcode {
+    Read = 1,
+    Write = 2,
+    Erase,
+}
+
+#[derive(StructDeser, Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+enum Status {
+    Ok,
+    Busy = 0x10,
+    #[struct_deser(other)]
+    Unknown(u8),
+}
+
+#[derive(StructDeser, Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+enum Mode {
+    Off = 1,
+    #[struct_deser(other)]
+    Other(u8),
+    On,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Request {
+    opcode: Opcode,
+    status: Status,
+    #[le]
+    addr: u32,
+}
+
+#[test]
+fn discriminants() {
+    assert_eq!(Opcode::BYTE_LEN, 2);
+    assert_eq!(Opcode::from_bytes(&[0, 1]), Opcode::Read);
+    assert_eq!(Opcode::from_bytes(&[0, 2]), Opcode::Write);
+    assert_eq!(Opcode::from_bytes(&[0, 3]), Opcode::Erase);
+
+    let mut bytes = [0; 2];
+    Opcode::Erase.into_bytes(&mut bytes);
+    assert_eq!(bytes, [0, 3]);
+}
+
+#[test]
+fn unknown_discriminant() {
+    assert_eq!(
+        Opcode::try_from_bytes(&[0, 4]),
+        Err(Error::UnknownDiscriminant {
+            type_name: "Opcode",
+            field: None,
+        })
+    );
+    assert_eq!(
+        Request::try_from_bytes(&[0, 42, 0, 0, 0, 0, 0]),
+        Err(Error::UnknownDiscriminant {
+            type_name: "Opcode",
+            field: Some("opcode"),
+        })
+    );
+}
+
+#[test]
+fn catch_all() {
+    assert_eq!(Status::from_bytes(&[0]), Status::Ok);
+    assert_eq!(Status::from_bytes(&[0x10]), Status::Busy);
+    assert_eq!(Status::from_bytes(&[0x42]), Status::Unknown(0x42));
+
+    let mut bytes = [0; 1];
+    Status::Unknown(0x42).into_bytes(&mut bytes);
+    assert_eq!(bytes, [0x42]);
+}
+
+#[test]
+fn discriminant_after_catch_all() {
+    // Rust counts the catch-all variant when numbering the following ones
+    let on = Mode::On;
+    let discriminant = unsafe { *(&on as *const Mode as *const u8) };
+    assert_eq!(discriminant, 3);
+
+    let mut bytes = [0; 1];
+    Mode::On.into_bytes(&mut bytes);
+    assert_eq!(bytes, [3]);
+    assert_eq!(Mode::from_bytes(&[3]), Mode::On);
+    assert_eq!(Mode::from_bytes(&[2]), Mode::Other(2));
+}
+
+#[test]
+fn in_struct() {
+    let request = Request {
+        opcode: Opcode::Write,
+        status: Status::Busy,
+        addr: 1,
+    };
+    let mut bytes = [0; Request::BYTE_LEN];
+    request.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0, 2, 0x10, 1, 0, 0, 0]);
+    assert_eq!(Request::from_bytes(&bytes), request);
+}
@@ -0,0 +1,109 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{BitRange, FieldByteOrder, FieldInfo};
+
+#[derive(StructDeser)]
+#[struct_deser(byte_order = "be", magic = "H")]
+struct Header {
+    #[bits = 4]
+    version: u8,
+    #[bits = 4]
+    ihl: u8,
+    #[le]
+    #[struct_deser(pad = 1)]
+    len: u16,
+    flags: [u8; 2],
+}
+
+#[derive(StructDeser)]
+#[struct_deser(ordered)]
+struct Ordered(u32, #[ne] u16);
+
+#[derive(StructDeser)]
+struct Generic<T> {
+    first: T,
+    second: u8,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be", view)]
+struct Raw {
+    len: u16,
+    r#type: u8,
+}
+
+// Usable in const contexts
+const LEN_OFFSET: usize = Header::OFFSET_LEN;
+
+#[test]
+fn fields() {
+    assert_eq!(Header::OFFSET_VERSION, 1);
+    assert_eq!(Header::OFFSET_IHL, 1);
+    assert_eq!(LEN_OFFSET, 3);
+    assert_eq!(Header::OFFSET_FLAGS, 5);
+
+    assert_eq!(
+        Header::FIELDS,
+        &[
+            FieldInfo {
+                name: "version",
+                offset: 1,
+                byte_len: 1,
+                byte_order: FieldByteOrder::Big,
+                type_name: "u8",
+                bits: Some(BitRange { shift: 4, width: 4 }),
+            },
+            FieldInfo {
+                name: "ihl",
+                offset: 1,
+                byte_len: 1,
+                byte_order: FieldByteOrder::Big,
+                type_name: "u8",
+                bits: Some(BitRange { shift: 0, width: 4 }),
+            },
+            FieldInfo {
+                name: "len",
+                offset: 3,
+                byte_len: 2,
+                byte_order: FieldByteOrder::Little,
+                type_name: "u16",
+                bits: None,
+            },
+            FieldInfo {
+                name: "flags",
+                offset: 5,
+                byte_len: 2,
+                byte_order: FieldByteOrder::Big,
+                type_name: stringify!([u8; 2]),
+                bits: None,
+            },
+        ][..]
+    );
+}
+
+#[test]
+fn ordered() {
+    assert_eq!(Ordered::OFFSET_1, 4);
+    assert_eq!(Ordered::FIELDS[0].byte_order, FieldByteOrder::Inherited);
+    assert_eq!(Ordered::FIELDS[1].byte_order, FieldByteOrder::Native);
+}
+
+#[test]
+fn generic() {
+    assert_eq!(Generic::<u32>::OFFSET_SECOND, 4);
+    assert_eq!(Generic::<u16>::FIELDS[1].offset, 2);
+    assert_eq!(Generic::<u16>::FIELDS[0].type_name, "T");
+}
+
+#[test]
+fn raw_identifier() {
+    assert_eq!(Raw::OFFSET_TYPE, 2);
+    assert_eq!(Raw::FIELDS[1].name, "type");
+
+    let mut bytes = [0, 1, 2];
+    assert_eq!(Raw::view(&bytes).r#type(), 2);
+    Raw::view_mut(&mut bytes).set_type(3);
+    assert_eq!(Raw::from(Raw::view(&bytes)), Raw { len: 1, r#type: 3 });
+}
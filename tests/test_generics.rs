@@ -0,0 +1,59 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{FromBytes, IntoBytes, SerializedByteLen};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Header {
+    kind: u8,
+    #[le]
+    seq: u32,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(identifier = "1", identifier_type = "u8")]
+struct Frame<P> {
+    #[be]
+    len: u16,
+    payload: P,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Samples<T>
+where
+    T: Copy,
+{
+    #[be]
+    samples: [T; 2],
+    count: u8,
+}
+
+#[test]
+fn generic_payload() {
+    assert_eq!(Frame::<Header>::BYTE_LEN, 7);
+    assert_eq!(Frame::<[u8; 3]>::BYTE_LEN, 5);
+
+    let frame = Frame {
+        len: 5,
+        payload: Header { kind: 1, seq: 2 },
+    };
+    let mut bytes = [0; Frame::<Header>::BYTE_LEN];
+    frame.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0, 5, 1, 2, 0, 0, 0]);
+    assert_eq!(Frame::from_bytes(&bytes), frame);
+}
+
+#[test]
+fn ordered_generic_field() {
+    let samples = Samples {
+        samples: [1u16, 2],
+        count: 2,
+    };
+    let mut bytes = [0; 5];
+    samples.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [0, 1, 0, 2, 2]);
+    assert_eq!(Samples::<u16>::from_bytes(&bytes), samples);
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "std")]
+
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use std::io::{self, Cursor};
+use struct_deser::io::{FromReader, IntoWriter};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be")]
+struct Record {
+    id: u16,
+    flags: u8,
+}
+
+#[derive(StructDeser, Debug, Copy, Clone, Eq, PartialEq)]
+#[struct_deser(repr = "u8")]
+enum Kind {
+    Data = 1,
+    Ack = 2,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Tagged {
+    kind: Kind,
+    #[struct_deser(const = "1")]
+    version: u8,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Large {
+    data: [u8; 300],
+    #[le]
+    chksum: u32,
+}
+
+#[test]
+fn read_write() {
+    let mut bytes = Vec::new();
+    Record { id: 1, flags: 2 }.write_to(&mut bytes).unwrap();
+    Record { id: 3, flags: 4 }.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes, [0, 1, 2, 0, 3, 4]);
+
+    let mut reader = Cursor::new(&bytes);
+    assert_eq!(
+        Record::read_from(&mut reader).unwrap(),
+        Record { id: 1, flags: 2 }
+    );
+    assert_eq!(
+        Record::read_from(&mut reader).unwrap(),
+        Record { id: 3, flags: 4 }
+    );
+    assert_eq!(
+        Record::read_from(&mut reader).unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn primitives() {
+    let mut bytes = Vec::new();
+    42u8.write_to(&mut bytes).unwrap();
+    [1u8, 2, 3].write_to(&mut bytes).unwrap();
+    assert_eq!(bytes, [42, 1, 2, 3]);
+
+    let mut reader = &bytes[..];
+    assert_eq!(u8::read_from(&mut reader).unwrap(), 42);
+    assert_eq!(<[u8; 3]>::read_from(&mut reader).unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn large() {
+    let large = Large {
+        data: [7; 300],
+        chksum: 47,
+    };
+
+    let mut bytes = Vec::new();
+    large.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 304);
+    assert_eq!(Large::read_from(&bytes[..]).unwrap(), large);
+}
+
+#[test]
+fn invalid_data() {
+    let mut bytes = Vec::new();
+    Tagged {
+        kind: Kind::Ack,
+        version: 1,
+    }
+    .write_to(&mut bytes)
+    .unwrap();
+    assert_eq!(bytes, [2, 1]);
+    assert_eq!(
+        Tagged::read_from(&bytes[..]).unwrap(),
+        Tagged {
+            kind: Kind::Ack,
+            version: 1,
+        }
+    );
+
+    let error = Tagged::read_from(&[9u8, 1][..]).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    let error = Tagged::read_from(&[1u8, 2][..]).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn error_conversion() {
+    let error = io::Error::from(struct_deser::Error::BufferTooSmall {
+        required: 4,
+        actual: 2,
+    });
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
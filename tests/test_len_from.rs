@@ -0,0 +1,132 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, ParseBytes, WriteBytes, U24};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Record<'a> {
+    #[be]
+    name_len: u16,
+    kind: u8,
+    #[struct_deser(len_from = "name_len")]
+    name: &'a [u8],
+    #[be]
+    ttl: u32,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "le")]
+struct Table {
+    count: u8,
+    data_len: U24,
+    #[struct_deser(len_from = "count")]
+    entries: Vec<u16>,
+    #[struct_deser(len_from = "data_len")]
+    data: Vec<u8>,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Chunk<'a>(u8, #[struct_deser(len_from = "0")] Vec<[u8; 2]>, &'a [u8]);
+
+#[test]
+fn borrowed() {
+    assert_eq!(Record::HEADER_LEN, 2 + 1 + 4);
+
+    let bytes = [0, 3, 7, b'f', b'o', b'o', 0, 0, 1, 0, 0xff];
+    let (record, len) = Record::parse(&bytes).unwrap();
+
+    assert_eq!(
+        record,
+        Record {
+            name_len: 3,
+            kind: 7,
+            name: b"foo",
+            ttl: 256,
+        }
+    );
+    assert_eq!(len, 10);
+
+    let mut out = [0; 10];
+    assert_eq!(record.serialized_len(), 10);
+    assert_eq!(record.write_bytes(&mut out), Ok(10));
+    assert_eq!(out, bytes[..10]);
+}
+
+#[test]
+fn length_written_from_field() {
+    let record = Record {
+        name_len: 42,
+        kind: 7,
+        name: b"ab",
+        ttl: 1,
+    };
+    let mut out = [0; 9];
+
+    assert_eq!(record.write_bytes(&mut out), Ok(9));
+    assert_eq!(out, [0, 2, 7, b'a', b'b', 0, 0, 0, 1]);
+}
+
+#[test]
+fn truncated() {
+    assert_eq!(
+        Record::parse(&[0, 3, 7, b'f', b'o']),
+        Err(Error::InvalidLength {
+            expected: 6,
+            actual: 5,
+            field: Some("name"),
+        })
+    );
+    assert_eq!(
+        Record::parse(&[0, 1, 7, b'f', 0, 0]),
+        Err(Error::InvalidLength {
+            expected: 8,
+            actual: 6,
+            field: Some("ttl"),
+        })
+    );
+}
+
+#[test]
+fn owned() {
+    let table = Table {
+        count: 0,
+        data_len: U24(0),
+        entries: vec![1, 0x0302],
+        data: vec![9, 8, 7],
+    };
+    let mut out = [0; 12];
+
+    assert_eq!(table.write_bytes(&mut out), Ok(11));
+    assert_eq!(out, [2, 3, 0, 0, 1, 0, 2, 3, 9, 8, 7, 0]);
+
+    let (parsed, len) = Table::parse(&out).unwrap();
+    assert_eq!(len, 11);
+    assert_eq!(
+        parsed,
+        Table {
+            count: 2,
+            data_len: U24(3),
+            ..table
+        }
+    );
+}
+
+#[test]
+fn length_out_of_range() {
+    let chunk = Chunk(0, vec![[0; 2]; 256], &[]);
+    let mut out = [0; 1024];
+
+    assert_eq!(
+        chunk.write_bytes(&mut out),
+        Err(Error::OutOfRange { field: Some("0") })
+    );
+}
+
+#[test]
+fn tuple_with_payload() {
+    let (chunk, len) = Chunk::parse(&[1, 4, 2, 3]).unwrap();
+
+    assert_eq!(chunk, Chunk(1, vec![[4, 2]], &[3]));
+    assert_eq!(len, 4);
+}
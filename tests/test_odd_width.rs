@@ -0,0 +1,58 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{
+    Error, FromBytes, IntoBytes, SerializedByteLen, TryIntoBytes, U24, U40, U48, U56,
+};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Frame {
+    #[be]
+    counter: U24,
+    #[le]
+    mac: U48,
+    #[be]
+    u40: U40,
+    #[le]
+    u56: U56,
+}
+
+#[test]
+fn roundtrip() {
+    assert_eq!(Frame::BYTE_LEN, 3 + 6 + 5 + 7);
+
+    let frame = Frame {
+        counter: U24(0x010203),
+        mac: U48(0x0a0b0c0d0e0f),
+        u40: U40(42),
+        u56: U56::new(U56::MAX).unwrap(),
+    };
+    let mut bytes = [0; Frame::BYTE_LEN];
+    frame.into_bytes(&mut bytes);
+
+    assert_eq!(&bytes[..9], &[1, 2, 3, 0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a]);
+    assert_eq!(&bytes[9..14], &[0, 0, 0, 0, 42]);
+    assert_eq!(&bytes[14..], &[0xff; 7]);
+    assert_eq!(Frame::from_bytes(&bytes), frame);
+}
+
+#[test]
+fn out_of_range() {
+    assert_eq!(U24::new(1 << 24), None);
+
+    let frame = Frame {
+        counter: U24(1 << 24),
+        mac: U48(0),
+        u40: U40(0),
+        u56: U56(0),
+    };
+    let mut bytes = [0; Frame::BYTE_LEN];
+
+    assert_eq!(
+        frame.try_into_bytes(&mut bytes),
+        Err(Error::OutOfRange {
+            field: Some("counter"),
+        })
+    );
+}
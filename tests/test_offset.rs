@@ -0,0 +1,86 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{FromBytes, IntoBytes, ParseBytes, SerializedByteLen, WriteBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be", size = 0x10)]
+struct Registers {
+    id: u8,
+    #[struct_deser(offset = 0x04)]
+    control: u16,
+    // Directly follows `control`
+    #[struct_deser(offset = 0x06)]
+    mask: u16,
+    #[struct_deser(offset = 0x0c, fill = 0xff)]
+    status: u32,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(magic = "AB")]
+struct WithMagic {
+    #[struct_deser(offset = 4)]
+    value: u8,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Generic<T> {
+    first: T,
+    #[struct_deser(offset = 8)]
+    second: u8,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Variable {
+    len: u8,
+    #[struct_deser(offset = 2, len_from = "len")]
+    data: Vec<u8>,
+}
+
+#[test]
+fn gaps() {
+    assert_eq!(Registers::BYTE_LEN, 0x10);
+
+    let registers = Registers {
+        id: 1,
+        control: 0x0203,
+        mask: 0x0405,
+        status: 0x06070809,
+    };
+    let mut bytes = [0x42; Registers::BYTE_LEN];
+    registers.into_bytes(&mut bytes);
+
+    assert_eq!(
+        bytes,
+        [1, 0, 0, 0, 2, 3, 4, 5, 0xff, 0xff, 0xff, 0xff, 6, 7, 8, 9]
+    );
+    assert_eq!(Registers::from_bytes(&bytes), registers);
+}
+
+#[test]
+fn offset_includes_magic() {
+    let mut bytes = [0; WithMagic::BYTE_LEN];
+    WithMagic { value: 7 }.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, *b"AB\0\0\x07");
+}
+
+#[test]
+fn generic() {
+    assert_eq!(<Generic<u16> as SerializedByteLen>::BYTE_LEN, 9);
+    assert_eq!(<Generic<[u8; 8]> as SerializedByteLen>::BYTE_LEN, 9);
+}
+
+#[test]
+fn variable() {
+    let value = Variable {
+        len: 2,
+        data: vec![3, 4],
+    };
+    let mut bytes = [0x42; 4];
+
+    assert_eq!(value.write_bytes(&mut bytes), Ok(4));
+    assert_eq!(bytes, [2, 0, 3, 4]);
+    assert_eq!(Variable::parse(&bytes), Ok((value, 4)));
+}
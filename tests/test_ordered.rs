@@ -0,0 +1,98 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::byteorder::{BE, LE};
+use struct_deser::{Error, SerializedByteLen};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(ordered)]
+struct Header {
+    kind: u8,
+    len: u16,
+    #[be]
+    magic: u16,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Container {
+    #[le]
+    le: Header,
+    #[be]
+    be: Header,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(ordered, byte_order = "le")]
+struct WithDefault(u32);
+
+#[test]
+fn caller_chooses_byte_order() {
+    use struct_deser::{FromBytesOrdered, IntoBytesOrdered};
+
+    let header = Header {
+        kind: 1,
+        len: 2,
+        magic: 3,
+    };
+    let mut bytes = [0; Header::BYTE_LEN];
+
+    header.into_bytes::<LE>(&mut bytes);
+    assert_eq!(bytes, [1, 2, 0, 0, 3]);
+    assert_eq!(Header::from_bytes::<LE>(&bytes), header);
+
+    header.into_bytes::<BE>(&mut bytes);
+    assert_eq!(bytes, [1, 0, 2, 0, 3]);
+    assert_eq!(Header::from_bytes::<BE>(&bytes), header);
+}
+
+#[test]
+fn nested() {
+    use struct_deser::{FromBytes, IntoBytes};
+
+    let container = Container {
+        le: Header {
+            kind: 1,
+            len: 2,
+            magic: 3,
+        },
+        be: Header {
+            kind: 4,
+            len: 5,
+            magic: 6,
+        },
+    };
+    let mut bytes = [0; Container::BYTE_LEN];
+    container.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [1, 2, 0, 0, 3, 4, 0, 5, 0, 6]);
+    assert_eq!(Container::from_bytes(&bytes), container);
+}
+
+#[test]
+fn fallible() {
+    use struct_deser::TryFromBytesOrdered;
+
+    assert_eq!(
+        Header::try_from_bytes::<BE>(&[1, 0]),
+        Err(Error::InvalidLength {
+            expected: 5,
+            actual: 2,
+            field: Some("len"),
+        })
+    );
+}
+
+#[test]
+fn default_byte_order() {
+    use struct_deser::{FromBytes, FromBytesOrdered};
+
+    assert_eq!(
+        <WithDefault as FromBytes>::from_bytes(&[1, 0, 0, 0]),
+        WithDefault(1)
+    );
+    assert_eq!(
+        <WithDefault as FromBytesOrdered>::from_bytes::<BE>(&[0, 0, 0, 1]),
+        WithDefault(1)
+    );
+}
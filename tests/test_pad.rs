@@ -0,0 +1,83 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, FromBytes, IntoBytes, ParseBytes, SerializedByteLen, TryFromBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be", pad = 1)]
+struct CLayout {
+    tag: u8,
+    #[struct_deser(pad = 3)]
+    value: u32,
+    #[struct_deser(skip_bytes = 2, fill = 0xaa)]
+    flags: u16,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Verified {
+    #[bits = 4]
+    high: u8,
+    #[bits = 4]
+    low: u8,
+    #[struct_deser(pad = 2, verify_pad)]
+    value: u8,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Variable<'a> {
+    len: u8,
+    #[struct_deser(len_from = "len", pad = 1)]
+    data: &'a [u8],
+}
+
+#[test]
+fn layout() {
+    assert_eq!(CLayout::BYTE_LEN, 1 + 3 + 4 + 2 + 2 + 1);
+
+    let value = CLayout {
+        tag: 7,
+        value: 0x01020304,
+        flags: 0x0506,
+    };
+    let mut bytes = [0x11; CLayout::BYTE_LEN];
+    value.into_bytes(&mut bytes);
+
+    assert_eq!(bytes, [7, 0, 0, 0, 1, 2, 3, 4, 0xaa, 0xaa, 5, 6, 0]);
+
+    bytes[1] = 0x42;
+    assert_eq!(CLayout::from_bytes(&bytes), value);
+}
+
+#[test]
+fn verified() {
+    assert_eq!(
+        Verified::try_from_bytes(&[0x12, 0, 0, 3]),
+        Ok(Verified {
+            high: 1,
+            low: 2,
+            value: 3,
+        })
+    );
+    assert_eq!(
+        Verified::try_from_bytes(&[0x12, 0, 1, 3]),
+        Err(Error::InvalidConstant {
+            type_name: "Verified",
+            field: None,
+        })
+    );
+}
+
+#[test]
+fn before_variable_field() {
+    let (value, len) = Variable::parse(&[2, 0xff, 1, 2]).unwrap();
+
+    assert_eq!(
+        value,
+        Variable {
+            len: 2,
+            data: &[1, 2],
+        }
+    );
+    assert_eq!(len, 4);
+}
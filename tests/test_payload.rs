@@ -0,0 +1,99 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, ParseBytes, WriteBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(identifier = "1", identifier_type = "u8")]
+struct Datagram<'a> {
+    #[be]
+    src_port: u16,
+    #[be]
+    dst_port: u16,
+    payload: &'a [u8],
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(identifier = "2", identifier_type = "u8")]
+struct Ack(u8);
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+enum Message<'a> {
+    Datagram(Datagram<'a>),
+    Ack(Ack),
+}
+
+#[test]
+fn parse() {
+    assert_eq!(Datagram::HEADER_LEN, 4);
+
+    let bytes = [0, 53, 4, 0, 1, 2, 3];
+    let (datagram, len) = Datagram::parse(&bytes).unwrap();
+
+    assert_eq!(datagram.src_port, 53);
+    assert_eq!(datagram.dst_port, 1024);
+    assert_eq!(datagram.payload, &[1, 2, 3]);
+    assert_eq!(len, bytes.len());
+}
+
+#[test]
+fn parse_empty_payload() {
+    let (datagram, len) = Datagram::parse(&[0, 53, 4, 0]).unwrap();
+
+    assert_eq!(datagram.payload, &[]);
+    assert_eq!(len, 4);
+}
+
+#[test]
+fn parse_short_header() {
+    assert_eq!(
+        Datagram::parse(&[0, 53, 4]),
+        Err(Error::InvalidLength {
+            expected: 4,
+            actual: 3,
+            field: Some("dst_port"),
+        })
+    );
+}
+
+#[test]
+fn write() {
+    let datagram = Datagram {
+        src_port: 53,
+        dst_port: 1024,
+        payload: &[1, 2, 3],
+    };
+    let mut bytes = [0; 8];
+
+    assert_eq!(datagram.serialized_len(), 7);
+    assert_eq!(datagram.write_bytes(&mut bytes), Ok(7));
+    assert_eq!(bytes, [0, 53, 4, 0, 1, 2, 3, 0]);
+    assert_eq!(
+        datagram.write_bytes(&mut bytes[..6]),
+        Err(Error::BufferTooSmall {
+            required: 7,
+            actual: 6,
+        })
+    );
+}
+
+#[test]
+fn tagged_enum() {
+    let bytes = [1, 0, 53, 4, 0, 42];
+    let (message, len) = Message::parse(&bytes).unwrap();
+
+    assert_eq!(
+        message,
+        Message::Datagram(Datagram {
+            src_port: 53,
+            dst_port: 1024,
+            payload: &[42],
+        })
+    );
+    assert_eq!(len, bytes.len());
+
+    let mut out = [0; 6];
+    assert_eq!(message.write_bytes(&mut out), Ok(6));
+    assert_eq!(out, bytes);
+}
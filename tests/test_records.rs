@@ -0,0 +1,146 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{records, records_mut, try_records, Error};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be", view)]
+struct Record {
+    id: u16,
+    flags: u8,
+}
+
+#[derive(StructDeser, Debug, Copy, Clone, Eq, PartialEq)]
+#[struct_deser(repr = "u8")]
+enum Kind {
+    Data = 1,
+    Ack = 2,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be")]
+struct Tagged {
+    id: u16,
+    kind: Kind,
+}
+
+const BYTES: [u8; 9] = [0, 1, 10, 0, 2, 20, 0, 3, 30];
+
+#[test]
+fn iterate() {
+    let records = records::<Record>(&BYTES).unwrap();
+    assert_eq!(records.len(), 3);
+
+    let ids = records.map(|record| record.id).collect::<Vec<_>>();
+    assert_eq!(ids, [1, 2, 3]);
+}
+
+#[test]
+fn double_ended() {
+    let mut records = records::<Record>(&BYTES).unwrap();
+
+    assert_eq!(records.next_back(), Some(Record { id: 3, flags: 30 }));
+    assert_eq!(records.next(), Some(Record { id: 1, flags: 10 }));
+    assert_eq!(records.len(), 1);
+    assert_eq!(records.next_back(), Some(Record { id: 2, flags: 20 }));
+    assert_eq!(records.next(), None);
+    assert_eq!(records.next_back(), None);
+}
+
+#[test]
+fn index() {
+    let mut records = records::<Record>(&BYTES).unwrap();
+
+    assert_eq!(records.get(2), Some(Record { id: 3, flags: 30 }));
+    assert_eq!(records.get(3), None);
+    assert_eq!(records.get(usize::MAX), None);
+    assert_eq!(records.nth(1), Some(Record { id: 2, flags: 20 }));
+    assert_eq!(records.get(0), Some(Record { id: 3, flags: 30 }));
+    assert_eq!(records.as_bytes(), &[0, 3, 30]);
+    assert_eq!(records.nth(5), None);
+}
+
+#[test]
+fn partial_record() {
+    assert_eq!(
+        records::<Record>(&BYTES[..7]).unwrap_err(),
+        Error::InvalidLength {
+            expected: 9,
+            actual: 7,
+            field: None,
+        }
+    );
+    assert_eq!(records::<Record>(&[]).unwrap().len(), 0);
+}
+
+#[test]
+fn primitives() {
+    let bytes = [1, 2, 3];
+    assert_eq!(
+        records::<u8>(&bytes).unwrap().rev().collect::<Vec<_>>(),
+        [3, 2, 1]
+    );
+    assert_eq!(records::<[u8; 3]>(&bytes).unwrap().next(), Some([1, 2, 3]));
+}
+
+#[test]
+fn views() {
+    let mut views = records::<RecordView>(&BYTES).unwrap();
+
+    assert_eq!(views.next().unwrap().id(), 1);
+    assert_eq!(views.next_back().unwrap().flags(), 30);
+    assert_eq!(
+        Record::from(views.get(0).unwrap()),
+        Record { id: 2, flags: 20 }
+    );
+}
+
+#[test]
+fn update() {
+    let mut bytes = BYTES;
+
+    for mut record in records_mut::<RecordViewMut>(&mut bytes).unwrap() {
+        let id = record.id();
+        record.set_id(id * 2);
+    }
+    records_mut::<RecordViewMut>(&mut bytes)
+        .unwrap()
+        .nth(2)
+        .unwrap()
+        .set_flags(0);
+
+    assert_eq!(bytes, [0, 2, 10, 0, 4, 20, 0, 6, 0]);
+    assert!(records_mut::<RecordViewMut>(&mut bytes[..4]).is_err());
+}
+
+#[test]
+fn invalid_records() {
+    let bytes = [0, 1, 1, 0, 2, 9, 0, 3, 2];
+    let mut records = try_records::<Tagged>(&bytes).unwrap();
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(
+        records.next(),
+        Some(Ok(Tagged {
+            id: 1,
+            kind: Kind::Data,
+        }))
+    );
+    assert_eq!(
+        records.next(),
+        Some(Err(Error::UnknownDiscriminant {
+            type_name: "Kind",
+            field: Some("kind"),
+        }))
+    );
+    assert_eq!(
+        records.next_back(),
+        Some(Ok(Tagged {
+            id: 3,
+            kind: Kind::Ack,
+        }))
+    );
+    assert_eq!(records.next(), None);
+    assert!(try_records::<Tagged>(&bytes[..4]).is_err());
+}
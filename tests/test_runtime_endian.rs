@@ -0,0 +1,72 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{
+    Endian, FromBytes, FromBytesOrdered, IntoBytes, IntoBytesOrdered, SerializedByteLen,
+    TryFromBytesOrdered,
+};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Native {
+    #[ne]
+    value: u32,
+    #[struct_deser(byte_order = "ne")]
+    other: u16,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(ordered)]
+struct PcapHeader {
+    magic: u32,
+    version_major: u16,
+    version_minor: u16,
+    #[be]
+    fixed: u16,
+}
+
+#[test]
+fn native() {
+    let native = Native {
+        value: 0x01020304,
+        other: 0x0506,
+    };
+    let mut bytes = [0; Native::BYTE_LEN];
+    native.into_bytes(&mut bytes);
+
+    assert_eq!(&bytes[..4], &0x01020304u32.to_ne_bytes());
+    assert_eq!(&bytes[4..], &0x0506u16.to_ne_bytes());
+    assert_eq!(Native::from_bytes(&bytes), native);
+}
+
+#[test]
+fn runtime() {
+    let header = PcapHeader {
+        magic: 0xa1b2c3d4,
+        version_major: 2,
+        version_minor: 4,
+        fixed: 1,
+    };
+    let mut big = [0; PcapHeader::BYTE_LEN];
+    let mut little = [0; PcapHeader::BYTE_LEN];
+    header.into_bytes_with(&mut big, Endian::Big);
+    header.into_bytes_with(&mut little, Endian::Little);
+
+    assert_eq!(big, [0xa1, 0xb2, 0xc3, 0xd4, 0, 2, 0, 4, 0, 1]);
+    assert_eq!(little, [0xd4, 0xc3, 0xb2, 0xa1, 2, 0, 4, 0, 0, 1]);
+    assert_eq!(PcapHeader::from_bytes_with(&big, Endian::Big), header);
+    assert_eq!(
+        <PcapHeader as TryFromBytesOrdered>::try_from_bytes_with(&little, Endian::Little),
+        Ok(header)
+    );
+}
+
+#[test]
+fn native_constant() {
+    let bytes = 1u16.to_ne_bytes();
+
+    assert_eq!(
+        <u16 as FromBytesOrdered>::from_bytes_with(&bytes, Endian::NATIVE),
+        1
+    );
+}
@@ -0,0 +1,82 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, ParseBytes, WriteBytes};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(identifier = "1", identifier_type = "u16")]
+struct Ping {
+    #[be]
+    seq: u16,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(identifier = "0x0100", identifier_type = "u16")]
+struct Data {
+    #[le]
+    value: u32,
+    flags: u8,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(byte_order = "be")]
+enum Message {
+    Ping(Ping),
+    Data(Data),
+}
+
+#[test]
+fn parse() {
+    let (message, len) = Message::parse(&[0, 1, 0, 42, 0xff]).unwrap();
+    assert_eq!(message, Message::Ping(Ping { seq: 42 }));
+    assert_eq!(len, 4);
+
+    let (message, len) = Message::parse(&[1, 0, 1, 0, 0, 0, 2]).unwrap();
+    assert_eq!(message, Message::Data(Data { value: 1, flags: 2 }));
+    assert_eq!(len, 7);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        Message::parse(&[0, 2, 0, 0]),
+        Err(Error::UnknownIdentifier {
+            type_name: "Message",
+            field: None,
+        })
+    );
+    assert_eq!(
+        Message::parse(&[0]),
+        Err(Error::InvalidLength {
+            expected: 2,
+            actual: 1,
+            field: None,
+        })
+    );
+    assert_eq!(
+        Message::parse(&[0, 1, 0]),
+        Err(Error::InvalidLength {
+            expected: 2,
+            actual: 1,
+            field: None,
+        })
+    );
+}
+
+#[test]
+fn write() {
+    let message = Message::Data(Data { value: 1, flags: 2 });
+    let mut bytes = [0xff; 8];
+
+    assert_eq!(message.serialized_len(), 7);
+    assert_eq!(message.write_bytes(&mut bytes), Ok(7));
+    assert_eq!(bytes, [1, 0, 1, 0, 0, 0, 2, 0xff]);
+    assert_eq!(
+        message.write_bytes(&mut bytes[..6]),
+        Err(Error::BufferTooSmall {
+            required: 7,
+            actual: 6,
+        })
+    );
+}
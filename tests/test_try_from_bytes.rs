@@ -0,0 +1,138 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, SerializedByteLen, TryFromBytes, TryFromBytesOrdered};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Packet {
+    #[be]
+    version: u16,
+    ttl: u8,
+    #[le]
+    chksum: u32,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Tuple(u8, #[be] u16);
+
+#[test]
+fn valid() {
+    let packet = Packet::try_from_bytes(&[0, 1, 42, 47, 0, 0, 0]).unwrap();
+    assert_eq!(
+        packet,
+        Packet {
+            version: 1,
+            ttl: 42,
+            chksum: 47,
+        }
+    );
+}
+
+#[test]
+fn too_short() {
+    assert_eq!(
+        Packet::try_from_bytes(&[0, 1, 42, 47]),
+        Err(Error::InvalidLength {
+            expected: Packet::BYTE_LEN,
+            actual: 4,
+            field: Some("chksum"),
+        })
+    );
+    assert_eq!(
+        Packet::try_from_bytes(&[]),
+        Err(Error::InvalidLength {
+            expected: Packet::BYTE_LEN,
+            actual: 0,
+            field: Some("version"),
+        })
+    );
+    assert_eq!(
+        Tuple::try_from_bytes(&[0, 1]),
+        Err(Error::InvalidLength {
+            expected: Tuple::BYTE_LEN,
+            actual: 2,
+            field: Some("1"),
+        })
+    );
+}
+
+#[test]
+fn too_long() {
+    assert_eq!(
+        Packet::try_from_bytes(&[0; 8]),
+        Err(Error::InvalidLength {
+            expected: Packet::BYTE_LEN,
+            actual: 8,
+            field: None,
+        })
+    );
+}
+
+#[test]
+fn primitives() {
+    use struct_deser::byteorder::BE;
+
+    assert_eq!(u8::try_from_bytes(&[42]), Ok(42));
+    assert_eq!(<[u8; 2]>::try_from_bytes(&[1, 2]), Ok([1, 2]));
+    assert_eq!(
+        <u16 as TryFromBytesOrdered>::try_from_bytes::<BE>(&[0, 42]),
+        Ok(42)
+    );
+    assert_eq!(
+        <u32 as TryFromBytesOrdered>::try_from_bytes::<BE>(&[0, 42]),
+        Err(Error::InvalidLength {
+            expected: 4,
+            actual: 2,
+            field: None,
+        })
+    );
+}
+
+#[test]
+#[should_panic]
+fn from_bytes_panics() {
+    use struct_deser::FromBytes;
+
+    Packet::from_bytes(&[0, 1]);
+}
+
+// Implements only the infallible traits
+#[derive(Debug, Eq, PartialEq)]
+struct Manual(u8);
+
+impl SerializedByteLen for Manual {
+    const BYTE_LEN: usize = 1;
+}
+
+impl struct_deser::FromBytes for Manual {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Manual(bytes[0])
+    }
+}
+
+impl struct_deser::IntoBytes for Manual {
+    fn into_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = self.0;
+    }
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Outer {
+    #[be]
+    len: u16,
+    manual: Manual,
+}
+
+#[test]
+fn nested_infallible() {
+    use struct_deser::FromBytes;
+
+    assert_eq!(
+        Outer::from_bytes(&[0, 1, 42]),
+        Outer {
+            len: 1,
+            manual: Manual(42),
+        }
+    );
+}
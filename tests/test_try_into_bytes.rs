@@ -0,0 +1,106 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{Error, SerializedByteLen, TryIntoBytes, TryIntoBytesOrdered};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Packet {
+    #[be]
+    version: u16,
+    ttl: u8,
+    #[le]
+    chksum: u32,
+}
+
+#[test]
+fn larger_buffer() {
+    let packet = Packet {
+        version: 1,
+        ttl: 42,
+        chksum: 47,
+    };
+    let mut bytes = [0xff; 10];
+
+    assert_eq!(packet.try_into_bytes(&mut bytes), Ok(Packet::BYTE_LEN));
+    assert_eq!(bytes, [0, 1, 42, 47, 0, 0, 0, 0xff, 0xff, 0xff]);
+}
+
+#[test]
+fn too_small_buffer() {
+    let packet = Packet {
+        version: 1,
+        ttl: 42,
+        chksum: 47,
+    };
+    let mut bytes = [0; 6];
+
+    assert_eq!(
+        packet.try_into_bytes(&mut bytes),
+        Err(Error::BufferTooSmall {
+            required: Packet::BYTE_LEN,
+            actual: 6,
+        })
+    );
+}
+
+#[test]
+fn primitives() {
+    use struct_deser::byteorder::LE;
+
+    let mut bytes = [0; 3];
+    assert_eq!(42u8.try_into_bytes(&mut bytes), Ok(1));
+    assert_eq!(
+        TryIntoBytesOrdered::try_into_bytes::<LE>(&0x0102u16, &mut bytes[1..]),
+        Ok(2)
+    );
+    assert_eq!(bytes, [42, 2, 1]);
+    assert_eq!(
+        TryIntoBytesOrdered::try_into_bytes::<LE>(&42u32, &mut bytes),
+        Err(Error::BufferTooSmall {
+            required: 4,
+            actual: 3,
+        })
+    );
+}
+
+// Implements only the infallible traits
+#[derive(Debug, Eq, PartialEq)]
+struct Manual(u8);
+
+impl SerializedByteLen for Manual {
+    const BYTE_LEN: usize = 1;
+}
+
+impl struct_deser::FromBytes for Manual {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Manual(bytes[0])
+    }
+}
+
+impl struct_deser::IntoBytes for Manual {
+    fn into_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = self.0;
+    }
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+struct Outer {
+    #[be]
+    len: u16,
+    manual: Manual,
+}
+
+#[test]
+fn nested_infallible() {
+    use struct_deser::IntoBytes;
+
+    let outer = Outer {
+        len: 1,
+        manual: Manual(42),
+    };
+    let mut bytes = [0; 3];
+
+    outer.into_bytes(&mut bytes);
+    assert_eq!(bytes, [0, 1, 42]);
+}
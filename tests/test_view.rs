@@ -0,0 +1,81 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+use struct_deser::{IntoBytes, SerializedByteLen};
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(view)]
+pub struct Header {
+    #[bits = 4]
+    pub version: u8,
+    #[bits = 4]
+    pub ihl: u8,
+    #[be]
+    pub len: u16,
+    pub flags: Flags,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+#[struct_deser(view, byte_order = "le")]
+struct Counters {
+    rx: u32,
+    tx: u32,
+}
+
+#[derive(StructDeser, Debug, Eq, PartialEq)]
+pub struct Flags(u8);
+
+#[test]
+fn view() {
+    let header = Header {
+        version: 4,
+        ihl: 5,
+        len: 1500,
+        flags: Flags(2),
+    };
+    let mut bytes = [0; Header::BYTE_LEN];
+    header.into_bytes(&mut bytes);
+
+    let view = Header::view(&bytes);
+    assert_eq!(view.version(), 4);
+    assert_eq!(view.ihl(), 5);
+    assert_eq!(view.len(), 1500);
+    assert_eq!(view.flags(), Flags(2));
+    assert_eq!(view.as_bytes(), &bytes);
+    assert_eq!(Header::from(view), header);
+}
+
+#[test]
+fn view_mut() {
+    let mut bytes = [0x45, 0, 0, 0];
+    {
+        let mut view = Header::view_mut(&mut bytes);
+        view.set_ihl(6);
+        view.set_len(0x0102);
+        view.set_flags(Flags(3));
+
+        assert_eq!(view.version(), 4);
+        assert_eq!(view.as_view().ihl(), 6);
+    }
+
+    assert_eq!(bytes, [0x46, 1, 2, 3]);
+}
+
+#[test]
+fn default_byte_order() {
+    let mut bytes = [1, 0, 0, 0, 2, 0, 0, 0];
+    Counters::view_mut(&mut bytes).set_tx(3);
+
+    let view = Counters::view(&bytes);
+    assert_eq!(view.rx(), 1);
+    assert_eq!(view.tx(), 3);
+    assert_eq!(Counters::from(view), Counters { rx: 1, tx: 3 });
+}
+
+#[test]
+#[should_panic(expected = "failed to serialize Header::ihl")]
+fn bit_field_out_of_range() {
+    let mut bytes = [0; Header::BYTE_LEN];
+    Header::view_mut(&mut bytes).set_ihl(16);
+}
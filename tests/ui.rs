@@ -0,0 +1,7 @@
+extern crate trybuild;
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+struct Ip {
+    #[bits = 4]
+    #[struct_deser(const = "4")]
+    version: u8,
+    #[bits = 4]
+    ihl: u8,
+}
+
+fn main() {}
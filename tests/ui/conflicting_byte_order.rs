@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+struct Packet {
+    #[be]
+    #[le]
+    version: u16,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+#[struct_deser(identifier = "1", identifier_type = "u16")]
+struct Ping {
+    seq: u8,
+}
+
+#[derive(StructDeser)]
+enum Message {
+    Ping(Ping),
+}
+
+fn main() {}
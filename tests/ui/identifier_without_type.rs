@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+#[struct_deser(identifier = "1")]
+struct Ping {
+    seq: u8,
+}
+
+fn main() {}
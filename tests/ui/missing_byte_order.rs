@@ -0,0 +1,13 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+// The bit field is rejected, so the warning isn't followed by errors about unsatisfied bounds
+#[derive(StructDeser)]
+struct Packet {
+    version: u16,
+    #[bits = 4]
+    flags: u8,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+struct Packet {
+    #[be]
+    version: u32,
+    #[struct_deser(offset = 2)]
+    ttl: u8,
+}
+
+fn main() {}
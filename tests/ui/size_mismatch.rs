@@ -0,0 +1,13 @@
+extern crate struct_deser;
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+#[struct_deser(size = 4)]
+struct Packet {
+    #[be]
+    version: u16,
+    ttl: u8,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+union Value {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
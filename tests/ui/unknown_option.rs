@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct_deser_derive;
+
+#[derive(StructDeser)]
+struct Packet {
+    #[struct_deser(bee)]
+    version: u16,
+}
+
+fn main() {}